@@ -0,0 +1,109 @@
+#ifndef SI_H
+#define SI_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Bumped whenever a signature, struct layout or enum value below changes.
+ */
+#define SI_ABI_VERSION 1
+
+/**
+ * Status returned by every fallible ABI call.
+ */
+typedef enum SiStatus {
+  SI_STATUS_OK = 0,
+  SI_STATUS_NULL_ARGUMENT = 1,
+  SI_STATUS_INVALID_UTF8 = 2,
+  SI_STATUS_INVALID_FORMAT = 3,
+  SI_STATUS_PARSE_ERROR = 4,
+  SI_STATUS_VALIDATION_ERROR = 5,
+  SI_STATUS_CALCULATION_ERROR = 6,
+  SI_STATUS_SERIALIZE_ERROR = 7,
+  SI_STATUS_PANIC = 99,
+} SiStatus;
+
+/**
+ * Output format flag accepted by `si_illustrate`.
+ */
+typedef enum SiOutputFormat {
+  SI_OUTPUT_FORMAT_CSV = 0,
+  SI_OUTPUT_FORMAT_JSON = 1,
+} SiOutputFormat;
+
+/**
+ * Byte buffer owned by the library. `data` is NUL-terminated, `len` excludes the NUL.
+ * Release with `si_buffer_free`.
+ */
+typedef struct SiBuffer {
+  uint8_t *data;
+  size_t len;
+} SiBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * ABI version of this library. Callers should refuse to run on a mismatch.
+ */
+uint32_t si_abi_version(void);
+
+/**
+ * Engine (crate) version as a static NUL-terminated string. Do not free.
+ */
+const char *si_engine_version(void);
+
+/**
+ * Validates the policy JSON and projects all base scenarios.
+ *
+ * On `SI_STATUS_OK`, `out` holds the illustration in the requested format.
+ * On any other status, `out` holds a UTF-8 error message.
+ * For `SI_STATUS_VALIDATION_ERROR` the message is a JSON array of issues `{"code", "pointer", "severity", "message", "params"}`,
+ * where `pointer` is the JSON pointer of the offending field, e.g. `/base/withdrawal_plan/1/from`.
+ * In both cases the buffer must be released with `si_buffer_free`.
+ *
+ * # Safety
+ *
+ * `policy_json` must be null or a valid NUL-terminated string.
+ * `out` must be null or point to writable memory for one `SiBuffer`.
+ */
+enum SiStatus si_illustrate(const char *policy_json,
+                            uint32_t format,
+                            struct SiBuffer *out);
+
+/**
+ * Same as `si_illustrate`, with the run basis read from `config_json`,
+ * e.g. `{"arithmetic": "Decimal", "shock": {"coi_rate_mult": 1.1}}`. Omitted keys keep their defaults.
+ * A malformed config is reported as `SI_STATUS_PARSE_ERROR`.
+ *
+ * # Safety
+ *
+ * `policy_json` and `config_json` must be null or valid NUL-terminated strings.
+ * `out` must be null or point to writable memory for one `SiBuffer`.
+ */
+enum SiStatus si_illustrate_with_config(const char *policy_json,
+                                        const char *config_json,
+                                        uint32_t format,
+                                        struct SiBuffer *out);
+
+/**
+ * Releases a buffer returned by this library. Freeing an empty buffer is a no-op.
+ *
+ * # Safety
+ *
+ * `buffer` must come from this library and must not be freed twice.
+ */
+void si_buffer_free(struct SiBuffer buffer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SI_H */
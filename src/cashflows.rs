@@ -1,2 +1,10 @@
 pub mod base_cf;
+pub mod batch_cf;
+pub mod bundle;
+pub mod explain;
 pub mod helpers;
+pub mod max_withdrawal;
+pub mod policy_cf;
+pub mod scenario;
+pub mod sensitivity;
+pub mod solver;
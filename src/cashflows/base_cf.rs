@@ -1,35 +1,74 @@
 mod fixed;
 mod varied;
 
-use crate::cashflows::base_cf::{fixed::fixed, varied::varied};
-use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+use crate::cashflows::base_cf::{
+    fixed::fixed,
+    varied::{varied, varied_run, varied_select},
+};
+use crate::cashflows::scenario::{RunConfig, Scenario, ScenarioSpec};
+use crate::database::product::ProductRules;
+use crate::enums::StatusEnum;
 use crate::structs::base::Base;
-use itertools::iproduct;
 use polars::prelude::*;
-use strum::IntoEnumIterator;
+use rayon::prelude::*;
 
-fn _scenario_cf(
-    scenario: (IntRateScenarioEnum, RiskTypeEnum, PremTermScenarioEnum),
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// Lapsed, terminated and claimed policies have nothing left to project
+fn _check_status(base: &Base) -> PolarsResult<()> {
+    if base.status != StatusEnum::Inforce {
+        let err_msg = format!(
+            "Policy status is {}; only in-force policies are projected",
+            base.status.as_ref()
+        );
+        return Err(PolarsError::ComputeError(err_msg.into()));
+    }
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Projects a single scenario of the base.
+pub fn scenario_cf(scenario: Scenario, base: &Base, config: &RunConfig) -> PolarsResult<LazyFrame> {
+    _check_status(base)?;
+    let rules = base.rules()?;
+    let lf = fixed(base, &rules, config)?;
+    varied(scenario, lf, base, &rules, config)
+}
+
+/// Projects a single scenario of the base, materialising only the listed columns.
+///
+/// Searches call it many times on the same product, so the rules are loaded once by the caller.
+pub fn scenario_cf_select(
+    scenario: Scenario,
     base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
+    columns: &[&str],
 ) -> PolarsResult<LazyFrame> {
-    let lf = fixed(base)?;
-    varied(scenario, lf, base)
+    _check_status(base)?;
+    let lf = fixed(base, rules, config)?;
+    varied_select(scenario, lf, base, rules, config, columns)
 }
 
-pub fn base_cf(base: &Base) -> PolarsResult<LazyFrame> {
-    let combination: Vec<_> = iproduct!(
-        IntRateScenarioEnum::iter(),
-        RiskTypeEnum::iter(),
-        PremTermScenarioEnum::iter()
-    )
-    .collect();
+/// Projects the scenarios of the spec, `ScenarioSpec::All` for the full illustration.
+///
+/// The fixed frame is built once and shared, and the scenarios run in parallel.
+/// Rows keep the scenario order, and the first failing scenario returns its error.
+/// The config sets the assumption basis of the run. The product rules are loaded once and shared too.
+pub fn base_cf(base: &Base, spec: &ScenarioSpec, config: &RunConfig) -> PolarsResult<LazyFrame> {
+    _check_status(base)?;
+    let rules = base.rules()?;
+    let runs = spec.runs(base, &rules, config)?;
+    let fixed_df = fixed(base, &rules, config)?.collect()?;
 
     // Process scenarios in parallel
-    let lfs = combination
-        .iter()
-        .map(|scenario| _scenario_cf(*scenario, base))
-        .collect::<PolarsResult<Vec<LazyFrame>>>()
-        .expect("Failed to collect DataFrames from scenarios");
+    let lfs = runs
+        .par_iter()
+        .map(|run| varied_run(run, fixed_df.clone().lazy(), base, &rules, config))
+        .collect::<PolarsResult<Vec<LazyFrame>>>()?;
 
     // Concatenate lazyframe
     concat(lfs, Default::default())
@@ -40,29 +79,189 @@ pub fn base_cf(base: &Base) -> PolarsResult<LazyFrame> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::read_json_struct;
+    use crate::enums::{
+        IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum, StatusEnum, ULEnum,
+    };
+    use crate::helpers::{df_to_csv, read_json_struct};
+    use crate::structs::inforce::{Inforce, PastWithdrawal};
     use crate::structs::policy::Policy;
-    use std::fs::File;
+    use chrono::NaiveDate;
 
     #[test]
     fn test_fn_base_cf() {
         // Generate result DataFrame from fixed function
         let json_path = "src/cashflows/test_data/uvl01_policy.json";
         let policy = read_json_struct::<Policy>(json_path).unwrap();
-        let df = base_cf(&policy.base).unwrap().collect().unwrap();
+        let df = base_cf(&policy.base, &ScenarioSpec::All, &RunConfig::default())
+            .unwrap()
+            .collect()
+            .unwrap();
 
-        // Export result df to csv to cross check in Excel file
+        // Exported to cross check in Excel file; refresh after an intended change with:
+        // UPDATE_RESULTS=1 cargo test test_fn_base_cf
         let export_path = "src/cashflows/test_data/result_base_cf.csv";
-        let mut export_file = File::create(export_path).unwrap();
-        CsvWriter::new(&mut export_file)
-            .finish(&mut df.clone())
+        let result = df_to_csv(&df).unwrap();
+        if std::env::var("UPDATE_RESULTS").is_ok() {
+            std::fs::write(export_path, &result).unwrap();
+        }
+
+        // Result in Excel file-varied sheet: Correct.
+        let expected = std::fs::read_to_string(export_path).unwrap();
+        assert!(
+            result == expected,
+            "DataFrames do not match {}.",
+            export_path
+        );
+    }
+
+    #[test]
+    fn test_fn_base_cf_error() {
+        // A scenario error is returned instead of panicking
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        // UVL02 has no extra premium rates
+        policy.base.id = ULEnum::UVL02;
+        assert!(base_cf(&policy.base, &ScenarioSpec::All, &RunConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_fn_base_cf_spec() {
+        // Two custom scenarios project two scenarios only
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let json = r#"{"Custom": [
+            {"name": "Mid 6%", "int_rate": [{"from": 1, "rate": 0.06}], "risk": "Standard", "tp_term": 10, "ep_term": 10},
+            {"name": "Zero", "int_rate": [{"from": 1, "rate": 0.0}], "risk": "Standard", "tp_term": 10, "ep_term": 10}
+        ]}"#;
+        let spec: ScenarioSpec = serde_json::from_str(json).unwrap();
+        let df = base_cf(&policy.base, &spec, &RunConfig::default())
+            .unwrap()
+            .collect()
             .unwrap();
 
-        // Import Excel range as df to check within Rust program
-        // TODO: Replace with actual Excel import logic
+        let term = policy.base.term(&policy.base.rules().unwrap()).unwrap() as usize;
+        assert_eq!(df.height(), 2 * term);
+        let names = df.column("int_rate_scenario").unwrap().str().unwrap();
+        assert_eq!(
+            (names.get(0), names.get(term)),
+            (Some("Mid 6%"), Some("Zero"))
+        );
+        // The 0% path is floored at the guaranteed rate
+        let rates = df.column("annual_int_rate").unwrap().f64().unwrap();
+        assert_eq!((rates.get(0), rates.get(term)), (Some(0.06), Some(0.02)));
+    }
 
-        // Assert that the result matches the expected DataFrame
-        // Result in Excel file-varied sheet: Correct.
-        assert!(true, "DataFrames do not match.");
+    #[test]
+    fn test_fn_base_cf_ep_term() {
+        // EP is paid over the EP term and TP over the TP term
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let json = r#"{"Custom": [
+            {"name": "Short EP", "int_rate": [{"from": 1, "rate": 0.06}], "risk": "Standard", "tp_term": 10, "ep_term": 5}
+        ]}"#;
+        let spec: ScenarioSpec = serde_json::from_str(json).unwrap();
+        let df = base_cf(&policy.base, &spec, &RunConfig::default())
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        let ep = df.column("ep").unwrap().f64().unwrap();
+        let tp = df.column("tp").unwrap().f64().unwrap();
+        assert_eq!((ep.get(4), ep.get(5)), (Some(policy.base.ep), Some(0.0)));
+        assert!(tp.get(9).unwrap() > 0.0);
+        assert_eq!(tp.get(10), Some(0.0));
+    }
+
+    #[test]
+    fn test_fn_base_cf_inforce_01() {
+        // Starting from the values at the end of year 7 reproduces the rest of the projection
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let scenario = (
+            IntRateScenarioEnum::Low,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        );
+        let columns = [
+            "year",
+            "age",
+            "cal_year",
+            "end_si",
+            "end_eav",
+            "end_tav",
+            "tav_withdrawal",
+            "lb",
+            "end_pav",
+        ];
+        let full = scenario_cf_select(
+            scenario,
+            &policy.base,
+            &rules,
+            &RunConfig::default(),
+            &columns,
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+        let values = |name: &str| full.column(name).unwrap().f64().unwrap().get(6).unwrap();
+
+        let tav_withdrawal = full.column("tav_withdrawal").unwrap().f64().unwrap();
+        policy.base.inforce = Some(Inforce {
+            valuation_date: NaiveDate::from_ymd_opt(2032, 2, 23).unwrap(),
+            si: values("end_si"),
+            eav: values("end_eav"),
+            tav: values("end_tav"),
+            withdrawal_history: (1..=7)
+                .map(|year| PastWithdrawal {
+                    year,
+                    tav_withdrawal: tav_withdrawal.get(year as usize - 1).unwrap(),
+                })
+                .collect(),
+        });
+        let inforce = scenario_cf_select(
+            scenario,
+            &policy.base,
+            &rules,
+            &RunConfig::default(),
+            &columns,
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+
+        let years = inforce.column("year").unwrap().i32().unwrap();
+        assert_eq!(years.get(0), Some(8));
+        assert_eq!(inforce.height(), full.height() - 7);
+        assert!(inforce.equals(&full.slice(7, full.height() - 7)));
+
+        // A TAV withdrawal in year 6 falls in the review window of the year 8 loyalty bonus
+        let lb = |df: &DataFrame| df.column("lb").unwrap().f64().unwrap().get(0).unwrap();
+        assert!(lb(&inforce) > 0.0);
+        let history = &mut policy.base.inforce.as_mut().unwrap().withdrawal_history;
+        history[5].tav_withdrawal = 1_000_000.0;
+        let forfeited = scenario_cf_select(
+            scenario,
+            &policy.base,
+            &rules,
+            &RunConfig::default(),
+            &columns,
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+        assert_eq!(lb(&forfeited), 0.0);
+    }
+
+    #[test]
+    fn test_fn_base_cf_inforce_02() {
+        // Claimed policies are not projected
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        policy.base.status = StatusEnum::Claimed;
+        let err = base_cf(&policy.base, &ScenarioSpec::All, &RunConfig::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Claimed"));
     }
 }
@@ -1,474 +1,697 @@
 use crate::cashflows::helpers::*;
+use crate::cashflows::scenario::{RunConfig, Scenario, ScenarioRun};
+use crate::database::product::ProductRules;
+use crate::database::rounding::RoundingRules;
+use crate::database::sb_rate::{SbRule, find_sb_rule};
 use crate::enums::{
-    DeathTPDBenefitEnum, IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum,
+    ArithmeticEnum, DeathTPDBenefitEnum, RoundedQuantityEnum::*, WithdrawalLogEnum,
 };
+use crate::num::Num;
 use crate::structs::base::Base;
 use polars::prelude::*;
-
-// Import the macro from the crate root due to #[macro_export]
-use crate::update_df_with_vectors;
+use rust_decimal::Decimal;
 
 // -----------------------------------------------------------------------------
 // PRIVATE
 // -----------------------------------------------------------------------------
 fn _varied_init(
-    scenario: (IntRateScenarioEnum, RiskTypeEnum, PremTermScenarioEnum),
+    run: &ScenarioRun,
     input_lf: LazyFrame,
     base: &Base,
-) -> PolarsResult<LazyFrame> {
-    // Interest rate and future value calculations
-    let annual_int_rate = match scenario.0 {
-        IntRateScenarioEnum::High => base.int_rate_tuple()?.0,
-        IntRateScenarioEnum::Low => base.int_rate_tuple()?.1,
-        _ => base.int_rate_tuple()?.2,
-    };
-    let monthly_int_rate = (1_f64 + annual_int_rate).powf(1_f64 / 12_f64) - 1_f64;
-    let due_a_n1_m12 = ((1_f64 + monthly_int_rate).powf(12_f64) - 1_f64)
-        / 12_f64
-        / (1_f64 - (1_f64 + monthly_int_rate).powf(-1_f64))
-        / (1_f64 + annual_int_rate);
-
-    // Define TP and EP term based on scenario
-    let term = match scenario.2 {
-        PremTermScenarioEnum::PolicyTerm => ("Policy", base.term()?, base.term()?),
-        PremTermScenarioEnum::OptedTerm => ("Opted", base.opted_tp_term, base.opted_ep_term),
-        _ => ("Must-pay", base.must_pay_period()?, base.must_pay_period()?),
-    };
+    rules: &ProductRules,
+) -> PolarsResult<DataFrame> {
+    // Crediting rate by policy year
+    let int_rate_lf = base
+        .credited_int_rate_lf(&run.declared_int_rate, rules)?
+        .select([
+            col("year"),
+            col("declared_int_rate").alias("curve_declared_int_rate"),
+            col("floor_int_rate").alias("curve_floor_int_rate"),
+            col("annual_int_rate").alias("curve_annual_int_rate"),
+        ]);
 
     // Create the lazy frame with fixed columns
     let df = input_lf
+        .left_join(int_rate_lf, "year", "year")
         .with_columns(vec![
             // Columns to identify the scenario
-            lit(scenario.0.as_ref()).alias("int_rate_scenario"),
-            lit(scenario.1.as_ref()).alias("risk_scenario"),
-            lit(term.0).alias("term_scenario"),
+            lit(run.int_rate_scenario.as_str()).alias("int_rate_scenario"),
+            lit(run.risk.as_ref()).alias("risk_scenario"),
+            lit(run.term_scenario.as_str()).alias("term_scenario"),
             // Premium term flags
-            col("year").lt_eq(lit(term.1)).alias("tp_term_flag"),
-            col("year").lt_eq(lit(term.2)).alias("ep_term_flag"),
-            // Interest rate based on scenario
-            lit(annual_int_rate).alias("annual_int_rate"),
-            lit(due_a_n1_m12).alias("due_a_n1_m12"),
+            col("year").lt_eq(lit(run.tp_term)).alias("tp_term_flag"),
+            col("year").lt_eq(lit(run.ep_term)).alias("ep_term_flag"),
+            // Interest rate based on scenario: declared, guaranteed floor and credited rate
+            col("curve_declared_int_rate").alias("declared_int_rate"),
+            col("curve_floor_int_rate").alias("floor_int_rate"),
+            col("curve_annual_int_rate").alias("annual_int_rate"),
+            // The Guaranteed scenario caps the admin charge at the guaranteed maximum
+            match run.max_admin_chrg {
+                Some(max_admin_chrg) => when(col("admin_chrg").gt(lit(max_admin_chrg)))
+                    .then(lit(max_admin_chrg))
+                    .otherwise(col("admin_chrg"))
+                    .alias("admin_chrg"),
+                None => col("admin_chrg"),
+            },
         ])
         .with_columns(vec![
-            // TP with big case bonus
-            (when(col("year").eq(lit(1)))
-                .then(lit(base.modal_tp_tuple()?.0 + base.big_case_bonus()?))
-                .otherwise(lit(base.modal_tp_tuple()?.0))
-                * col("tp_term_flag"))
-            .alias("tp"),
-            // EP
-            (lit(base.modal_ep_tuple()?.0) * col("tp_term_flag")).alias("ep"),
             // Risk flags
             col("risk_scenario").eq(lit("Subrisk")).alias("risk_flag"),
         ])
-        .with_columns(vec![
-            // Surrender charge
-            (col("tp") * col("srr_chrg_rate")).alias("srr_chrg"),
-            // Allocation charge
-            (col("tp") * col("tp_alloc_chrg_rate")).alias("tp_alloc_chrg"),
-            (col("ep") * col("ep_alloc_chrg_rate")).alias("ep_alloc_chrg"),
-        ])
-        .with_columns(vec![
-            // Premium allocation
-            (col("tp") - col("tp_alloc_chrg")).alias("tp_alloc"),
-            (col("ep") - col("ep_alloc_chrg")).alias("ep_alloc"),
-        ])
-        .with_columns(vec![
-            // Start values
-            lit(false).alias("cont_flag"),
-            lit(0.0).alias("start_si"),
-            lit(0.0).alias("start_eav"),
-            lit(0.0).alias("start_tav"),
-            lit(0.0).alias("start_pav"),
-            // Surrender charge and benefit values
-            lit(0.0).alias("srr_val"),
-            lit(0.0).alias("ben"),
-            lit(0.0).alias("acc_ben"),
-            // Withdrawal
-            lit(0.0).alias("withdrawal"),
-            lit(0.0).alias("eav_withdrawal"),
-            lit(0.0).alias("tav_withdrawal"),
-            lit("No withdrawal.").alias("withdrawal_log"),
-            // Load
-            lit(0.0).alias("unrounded_em_load"),
-            lit(0.0).alias("unrounded_pm_load"),
-            lit(0.0).alias("em_load"),
-            lit(0.0).alias("pm_load"),
-            lit(0.0).alias("load"),
-            lit(0.0).alias("load_alloc_chrg"),
-            lit(0.0).alias("load_alloc"),
-            lit(0.0).alias("alloc_chrg"),
-            lit(0.0).alias("alloc"),
-            // COI
-            lit(0.0).alias("sar"),
-            lit(0.0).alias("standard_coi"),
-            lit(0.0).alias("em_load_coi"),
-            lit(0.0).alias("pm_load_coi"),
-            lit(0.0).alias("acc_coi"),
-            lit(0.0).alias("coi"),
-            // Account values after withdrawal and allocation
-            lit(0.0).alias("eav_after_wdrl_and_alloc"),
-            lit(0.0).alias("tav_after_wdrl_and_alloc"),
-            lit(0.0).alias("pav_after_wdrl_and_alloc"),
-            // Deduction
-            lit(0.0).alias("plan_nom_deduction"),
-            lit(0.0).alias("plan_deduction"),
-            lit(false).alias("deduction_flag"),
-            lit(0.0).alias("nom_deduction"),
-            lit(0.0).alias("deduction"),
-            lit(0.0).alias("tav_deduction"),
-            lit(0.0).alias("eav_deduction"),
-            // Interest
-            lit(0.0).alias("eav_int"),
-            lit(0.0).alias("tav_int"),
-            lit(0.0).alias("int"),
-            // Bonus
-            lit(0.0).alias("lb_tav_withdrawal_review"),
-            lit(false).alias("lb_flag"),
-            lit(0.0).alias("lb"),
-            lit(0.0).alias("sb_rate"),
-            lit(0.0).alias("sb_tav_withdrawal_review"),
-            lit(false).alias("sb_flag"),
-            lit(0.0).alias("sb"),
-            lit(0.0).alias("bonus"),
-            // End values
-            lit(0.0).alias("end_si"),
-            lit(0.0).alias("end_eav"),
-            lit(0.0).alias("end_tav"),
-            lit(0.0).alias("end_pav"),
+        .drop([
+            "curve_declared_int_rate",
+            "curve_floor_int_rate",
+            "curve_annual_int_rate",
         ])
-        .collect()
-        .unwrap()
-        .lazy();
+        .collect()?;
     // Return result
     Ok(df)
 }
 
-// All the calculation for a single year must be completed then move on to another year
-fn _varied_row_by_row_cf(lf: LazyFrame, base: &Base) -> PolarsResult<LazyFrame> {
-    let df = lf.collect().unwrap();
-    let vec_length = df.height();
-    // Create vectors from dataframe columnss
-    // Start values
-    let mut cont_flag: Vec<bool> = col_to_vec_bool(&df, "cont_flag");
-    let mut start_si: Vec<f64> = col_to_vec_f64(&df, "start_si");
-    let mut start_tav: Vec<f64> = col_to_vec_f64(&df, "start_tav");
-    let mut start_eav: Vec<f64> = col_to_vec_f64(&df, "start_eav");
-    let mut start_pav: Vec<f64> = col_to_vec_f64(&df, "start_pav");
-
-    // Surrender
-    let srr_chrg: Vec<f64> = col_to_vec_f64(&df, "srr_chrg"); // Read only
-    let mut srr_val: Vec<f64> = col_to_vec_f64(&df, "srr_val");
-
-    // Benefits
-    let mut ben: Vec<f64> = col_to_vec_f64(&df, "ben");
-    let juvenile_lien_rate: Vec<f64> = col_to_vec_f64(&df, "juvenile_lien_rate"); // Read only
-    let acc_ben_term_flag: Vec<bool> = col_to_vec_bool(&df, "acc_ben_term_flag"); // Read only
-    let mut acc_ben: Vec<f64> = col_to_vec_f64(&df, "acc_ben");
+// Premiums, their allocation and the deduction factor, derived in the number type of the kernel
+struct VariedPrem<N> {
+    due_a_n1_m12: Vec<N>,
+    tp: Vec<N>,
+    ep: Vec<N>,
+    srr_chrg: Vec<N>,
+    tp_alloc_chrg: Vec<N>,
+    ep_alloc_chrg: Vec<N>,
+    tp_alloc: Vec<N>,
+    ep_alloc: Vec<N>,
+}
 
-    // Withdrawal
-    let withdrawal_input: Vec<f64> = col_to_vec_f64(&df, "withdrawal_input"); // Read only
-    let mut withdrawal: Vec<f64> = col_to_vec_f64(&df, "withdrawal");
-    let mut eav_withdrawal: Vec<f64> = col_to_vec_f64(&df, "eav_withdrawal");
-    let mut tav_withdrawal: Vec<f64> = col_to_vec_f64(&df, "tav_withdrawal");
-    let mut withdrawal_log: Vec<String> = col_to_vec_string(&df, "withdrawal_log");
-
-    // Premium load
-    let risk_flag: Vec<bool> = col_to_vec_bool(&df, "risk_flag"); // Read only
-    let pm_load_term_flag: Vec<bool> = col_to_vec_bool(&df, "pm_load_term_flag"); // Read only
-    let em_load_term_flag: Vec<bool> = col_to_vec_bool(&df, "em_load_term_flag"); // Read only
-    let mut unrounded_pm_load: Vec<f64> = col_to_vec_f64(&df, "unrounded_pm_load");
-    let mut unrounded_em_load: Vec<f64> = col_to_vec_f64(&df, "unrounded_em_load");
-    let mut pm_load: Vec<f64> = col_to_vec_f64(&df, "pm_load");
-    let mut em_load: Vec<f64> = col_to_vec_f64(&df, "em_load");
-    let mut load: Vec<f64> = col_to_vec_f64(&df, "load");
-    let mut load_alloc_chrg: Vec<f64> = col_to_vec_f64(&df, "load_alloc_chrg");
-    let mut load_alloc: Vec<f64> = col_to_vec_f64(&df, "load_alloc");
-
-    // Allocation
-    let tp_alloc_chrg: Vec<f64> = col_to_vec_f64(&df, "tp_alloc_chrg"); // Read only
-    let tp_alloc: Vec<f64> = col_to_vec_f64(&df, "tp_alloc"); // Read only
-    let ep_alloc_chrg_rate: Vec<f64> = col_to_vec_f64(&df, "ep_alloc_chrg_rate"); // Read only
-    let ep_alloc_chrg: Vec<f64> = col_to_vec_f64(&df, "ep_alloc_chrg"); // Read only
-    let ep_alloc: Vec<f64> = col_to_vec_f64(&df, "ep_alloc"); // Read only
-    let mut alloc_chrg: Vec<f64> = col_to_vec_f64(&df, "alloc_chrg");
-    let mut alloc: Vec<f64> = col_to_vec_f64(&df, "alloc");
+impl<N: Num> VariedPrem<N> {
+    fn new(df: &DataFrame, c: &VariedConsts<N>) -> PolarsResult<Self> {
+        let year: Vec<i32> = df.column("year")?.i32()?.into_no_null_iter().collect();
+        let tp_term_flag = _bool_vec(df, "tp_term_flag")?;
+        let ep_term_flag = _bool_vec(df, "ep_term_flag")?;
+        let annual_int_rate = _num_vec::<N>(df, "annual_int_rate")?;
+        let srr_chrg_rate = _num_vec::<N>(df, "srr_chrg_rate")?;
+        let tp_alloc_chrg_rate = _num_vec::<N>(df, "tp_alloc_chrg_rate")?;
+        let ep_alloc_chrg_rate = _num_vec::<N>(df, "ep_alloc_chrg_rate")?;
+
+        let (one, twelve) = (N::ONE, N::from_input(12.0)?);
+        let mut prem = Self::with_len(df.height());
+        for i in 0..df.height() {
+            // Factor converting 12 monthly deductions at the crediting rate
+            // A 0% rate leaves the deductions undiscounted
+            let int_rate = annual_int_rate[i];
+            prem.due_a_n1_m12[i] = if int_rate == N::ZERO {
+                one
+            } else {
+                let monthly_int_rate = (one + int_rate).root_12()? - one;
+                (((one + monthly_int_rate).pow(12)? - one) / twelve)
+                    .try_div(one - (one + monthly_int_rate).pow(-1)?)?
+                    .try_div(one + int_rate)?
+            };
+
+            // TP with big case bonus, and EP
+            let tp = if year[i] == 1 {
+                c.annual_tp + c.big_case_bonus
+            } else {
+                c.annual_tp
+            };
+            prem.tp[i] = tp * N::from_flag(tp_term_flag[i]);
+            prem.ep[i] = c.annual_ep * N::from_flag(ep_term_flag[i]);
+
+            // Surrender charge, allocation charge and premium allocation
+            prem.srr_chrg[i] = prem.tp[i] * srr_chrg_rate[i];
+            prem.tp_alloc_chrg[i] = prem.tp[i] * tp_alloc_chrg_rate[i];
+            prem.ep_alloc_chrg[i] = prem.ep[i] * ep_alloc_chrg_rate[i];
+            prem.tp_alloc[i] = prem.tp[i] - prem.tp_alloc_chrg[i];
+            prem.ep_alloc[i] = prem.ep[i] - prem.ep_alloc_chrg[i];
+        }
+        Ok(prem)
+    }
 
-    // Account values after withdrawal and allocation
-    let mut eav_after_wdrl_and_alloc: Vec<f64> = col_to_vec_f64(&df, "eav_after_wdrl_and_alloc");
-    let mut tav_after_wdrl_and_alloc: Vec<f64> = col_to_vec_f64(&df, "tav_after_wdrl_and_alloc");
-    let mut pav_after_wdrl_and_alloc: Vec<f64> = col_to_vec_f64(&df, "pav_after_wdrl_and_alloc");
-
-    // Cost of insurance
-    let coi_rate: Vec<f64> = col_to_vec_f64(&df, "coi_rate"); // Read only
-    let mut sar: Vec<f64> = col_to_vec_f64(&df, "sar");
-    let mut standard_coi: Vec<f64> = col_to_vec_f64(&df, "standard_coi");
-    let mut acc_coi: Vec<f64> = col_to_vec_f64(&df, "acc_coi");
-    let mut em_load_coi: Vec<f64> = col_to_vec_f64(&df, "em_load_coi");
-    let mut pm_load_coi: Vec<f64> = col_to_vec_f64(&df, "pm_load_coi");
-    let mut coi: Vec<f64> = col_to_vec_f64(&df, "coi");
+    fn with_len(n: usize) -> Self {
+        Self {
+            due_a_n1_m12: vec![N::ZERO; n],
+            tp: vec![N::ZERO; n],
+            ep: vec![N::ZERO; n],
+            srr_chrg: vec![N::ZERO; n],
+            tp_alloc_chrg: vec![N::ZERO; n],
+            ep_alloc_chrg: vec![N::ZERO; n],
+            tp_alloc: vec![N::ZERO; n],
+            ep_alloc: vec![N::ZERO; n],
+        }
+    }
 
-    // Interest
-    let annual_int_rate: Vec<f64> = col_to_vec_f64(&df, "annual_int_rate"); // Read only
-    let due_a_n1_m12: Vec<f64> = col_to_vec_f64(&df, "due_a_n1_m12"); // Read only
+    // The factor and premiums go before risk_flag and the allocation after it, as in the workbook
+    fn insert_into(self, df: &mut DataFrame) -> PolarsResult<()> {
+        let at = df.try_get_column_index("risk_flag")?;
+        let before = [
+            ("due_a_n1_m12", self.due_a_n1_m12),
+            ("tp", self.tp),
+            ("ep", self.ep),
+        ];
+        for (offset, (name, values)) in before.into_iter().enumerate() {
+            df.insert_column(at + offset, N::into_column(name, values)?)?;
+        }
+        let after = [
+            ("srr_chrg", self.srr_chrg),
+            ("tp_alloc_chrg", self.tp_alloc_chrg),
+            ("ep_alloc_chrg", self.ep_alloc_chrg),
+            ("tp_alloc", self.tp_alloc),
+            ("ep_alloc", self.ep_alloc),
+        ];
+        for (name, values) in after {
+            df.with_column(N::into_column(name, values)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn _num_vec<N: Num>(df: &DataFrame, name: &str) -> PolarsResult<Vec<N>> {
+    let values = df.column(name)?.f64()?.cont_slice()?;
+    values.iter().map(|v| N::from_input(*v)).collect()
+}
+
+fn _bool_vec(df: &DataFrame, name: &str) -> PolarsResult<Vec<bool>> {
+    Ok(df
+        .column(name)?
+        .bool()?
+        .into_iter()
+        .map(|v| v.unwrap_or(false))
+        .collect())
+}
+
+// Read-only inputs of the kernel in its number type, read from the frame built by _varied_init
+struct VariedInputs<N> {
+    srr_chrg: Vec<N>,
+    juvenile_lien_rate: Vec<N>,
+    acc_ben_term_flag: Vec<bool>,
+    withdrawal_input: Vec<N>,
+    risk_flag: Vec<bool>,
+    em_load_term_flag: Vec<bool>,
+    pm_load_term_flag: Vec<bool>,
+    tp_alloc_chrg: Vec<N>,
+    tp_alloc: Vec<N>,
+    ep_alloc_chrg_rate: Vec<N>,
+    ep_alloc_chrg: Vec<N>,
+    ep_alloc: Vec<N>,
+    coi_rate: Vec<N>,
+    annual_int_rate: Vec<N>,
+    due_a_n1_m12: Vec<N>,
+    admin_chrg: Vec<N>,
+    lb_rate: Vec<N>,
+}
+
+impl<N: Num> VariedInputs<N> {
+    fn new(df: &DataFrame, prem: &VariedPrem<N>) -> PolarsResult<Self> {
+        let num_vec = |name: &str| _num_vec::<N>(df, name);
+        let bool_vec = |name: &str| _bool_vec(df, name);
+        Ok(Self {
+            srr_chrg: prem.srr_chrg.clone(),
+            juvenile_lien_rate: num_vec("juvenile_lien_rate")?,
+            acc_ben_term_flag: bool_vec("acc_ben_term_flag")?,
+            withdrawal_input: num_vec("withdrawal_input")?,
+            risk_flag: bool_vec("risk_flag")?,
+            em_load_term_flag: bool_vec("em_load_term_flag")?,
+            pm_load_term_flag: bool_vec("pm_load_term_flag")?,
+            tp_alloc_chrg: prem.tp_alloc_chrg.clone(),
+            tp_alloc: prem.tp_alloc.clone(),
+            ep_alloc_chrg_rate: num_vec("ep_alloc_chrg_rate")?,
+            ep_alloc_chrg: prem.ep_alloc_chrg.clone(),
+            ep_alloc: prem.ep_alloc.clone(),
+            coi_rate: num_vec("coi_rate")?,
+            annual_int_rate: num_vec("annual_int_rate")?,
+            due_a_n1_m12: prem.due_a_n1_m12.clone(),
+            admin_chrg: num_vec("admin_chrg")?,
+            lb_rate: num_vec("lb_rate")?,
+        })
+    }
+}
+
+// Values of the base that do not change over the projection, looked up once per scenario
+struct VariedConsts<N> {
+    first_year: i32,
+    start_si: N,
+    start_eav: N,
+    start_tav: N,
+    tav_withdrawal_history: Vec<N>, // Policy years before first_year
+    option_a: bool,
+    acc_ben_coeff: N,
+    em_load: N,
+    pm_load: N,
+    extra_prem_rate: N,
+    acc_coi_rate: N,
+    annual_tp: N,
+    big_case_bonus: N,
+    annual_ep: N,
+    lb_review_period: usize,
+    sb_rules: Vec<SbRule>,
+    withdrawal_limits: WithdrawalLimits<N>,
+    rounding: RoundingRules,
+}
+
+impl<N: Num> VariedConsts<N> {
+    fn new(base: &Base, rules: &ProductRules) -> PolarsResult<Self> {
+        let first_year = base.first_year()?;
+        let (start_si, start_eav, start_tav, tav_withdrawal_history) = match &base.inforce {
+            Some(inforce) => (
+                inforce.si,
+                inforce.eav,
+                inforce.tav,
+                inforce.tav_withdrawals(first_year),
+            ),
+            None => (base.si, 0.0, 0.0, Vec::new()),
+        };
+        Ok(Self {
+            first_year,
+            start_si: N::from_input(start_si)?,
+            start_eav: N::from_input(start_eav)?,
+            start_tav: N::from_input(start_tav)?,
+            tav_withdrawal_history: tav_withdrawal_history
+                .into_iter()
+                .map(N::from_input)
+                .collect::<PolarsResult<Vec<N>>>()?,
+            option_a: base.death_tpd_option == DeathTPDBenefitEnum::A,
+            acc_ben_coeff: N::from_input(base.acc_ben_coeff as f64)?,
+            em_load: N::from_input(base.load.em_load)?,
+            pm_load: N::from_input(base.load.pm_load as f64)?,
+            extra_prem_rate: N::from_input(base.extra_prem_rate(rules)?)?,
+            acc_coi_rate: N::from_input(base.acc_coi_rate()?)?,
+            annual_tp: N::from_input(base.modal_tp_tuple(rules)?.0)?,
+            big_case_bonus: N::from_input(base.big_case_bonus(rules)?)?,
+            annual_ep: N::from_input(base.modal_ep_tuple(rules)?.0)?,
+            lb_review_period: base.lb_review_period()? as usize,
+            sb_rules: base.sb_rules()?,
+            withdrawal_limits: WithdrawalLimits::new(base, rules)?,
+            rounding: rules.rounding.clone(),
+        })
+    }
+}
+
+// Kernel vectors become f64 columns whatever the number type of the kernel
+trait IntoColumn: Sized {
+    fn into_column(name: &str, values: Vec<Self>) -> PolarsResult<Column>;
+}
+
+impl IntoColumn for bool {
+    fn into_column(name: &str, values: Vec<Self>) -> PolarsResult<Column> {
+        Ok(Column::new(name.into(), values))
+    }
+}
 
+impl IntoColumn for &'static str {
+    fn into_column(name: &str, values: Vec<Self>) -> PolarsResult<Column> {
+        Ok(Column::new(name.into(), values))
+    }
+}
+
+impl<N: Num> IntoColumn for N {
+    fn into_column(name: &str, values: Vec<Self>) -> PolarsResult<Column> {
+        let values = values
+            .into_iter()
+            .map(N::to_output)
+            .collect::<PolarsResult<Vec<f64>>>()?;
+        Ok(Column::new(name.into(), values))
+    }
+}
+
+// Struct-of-arrays state of one projection: one vector per column the kernel computes.
+// Years after a lapse keep the defaults.
+macro_rules! varied_state {
+    ($( $name:ident: $ty:ty = $default:expr ),* $(,)?) => {
+        struct VariedState<N> {
+            $( $name: Vec<$ty>, )*
+        }
+
+        impl<N: Num> VariedState<N> {
+            fn new(n: usize) -> Self {
+                Self {
+                    $( $name: vec![$default; n], )*
+                }
+            }
+
+            // Kernel columns in output order, keeping only the requested ones
+            fn into_columns(self, columns: Option<&[&str]>) -> PolarsResult<Vec<Column>> {
+                let mut result = Vec::new();
+                $(
+                    let name = stringify!($name);
+                    if columns.is_none_or(|columns| columns.contains(&name)) {
+                        result.push(IntoColumn::into_column(name, self.$name)?);
+                    }
+                )*
+                Ok(result)
+            }
+        }
+    };
+}
+
+varied_state! {
+    // Start values
+    cont_flag: bool = false,
+    start_si: N = N::ZERO,
+    start_eav: N = N::ZERO,
+    start_tav: N = N::ZERO,
+    start_pav: N = N::ZERO,
+    // Surrender charge and benefit values
+    srr_val: N = N::ZERO,
+    ben: N = N::ZERO,
+    acc_ben: N = N::ZERO,
+    // Withdrawal
+    withdrawal: N = N::ZERO,
+    eav_withdrawal: N = N::ZERO,
+    tav_withdrawal: N = N::ZERO,
+    withdrawal_log: &'static str = WithdrawalLogEnum::NoWithdrawal.into(),
+    // Load
+    unrounded_em_load: N = N::ZERO,
+    unrounded_pm_load: N = N::ZERO,
+    em_load: N = N::ZERO,
+    pm_load: N = N::ZERO,
+    load: N = N::ZERO,
+    load_alloc_chrg: N = N::ZERO,
+    load_alloc: N = N::ZERO,
+    alloc_chrg: N = N::ZERO,
+    alloc: N = N::ZERO,
+    // COI
+    sar: N = N::ZERO,
+    standard_coi: N = N::ZERO,
+    em_load_coi: N = N::ZERO,
+    pm_load_coi: N = N::ZERO,
+    acc_coi: N = N::ZERO,
+    coi: N = N::ZERO,
+    // Account values after withdrawal and allocation
+    eav_after_wdrl_and_alloc: N = N::ZERO,
+    tav_after_wdrl_and_alloc: N = N::ZERO,
+    pav_after_wdrl_and_alloc: N = N::ZERO,
     // Deduction
-    let admin_chrg: Vec<f64> = col_to_vec_f64(&df, "admin_chrg"); // Read only
-    let mut plan_nom_deduction: Vec<f64> = col_to_vec_f64(&df, "plan_nom_deduction");
-    let mut plan_deduction: Vec<f64> = col_to_vec_f64(&df, "plan_deduction");
-    let mut deduction_flag: Vec<bool> = col_to_vec_bool(&df, "deduction_flag");
-    let mut nom_deduction: Vec<f64> = col_to_vec_f64(&df, "nom_deduction");
-    let mut deduction: Vec<f64> = col_to_vec_f64(&df, "deduction");
-    let mut tav_deduction: Vec<f64> = col_to_vec_f64(&df, "tav_deduction");
-    let mut eav_deduction: Vec<f64> = col_to_vec_f64(&df, "eav_deduction");
+    plan_nom_deduction: N = N::ZERO,
+    plan_deduction: N = N::ZERO,
+    deduction_flag: bool = false,
+    nom_deduction: N = N::ZERO,
+    deduction: N = N::ZERO,
+    tav_deduction: N = N::ZERO,
+    eav_deduction: N = N::ZERO,
     // Interest
-    let mut eav_int: Vec<f64> = col_to_vec_f64(&df, "eav_int");
-    let mut tav_int: Vec<f64> = col_to_vec_f64(&df, "tav_int");
-    let mut int: Vec<f64> = col_to_vec_f64(&df, "int");
+    eav_int: N = N::ZERO,
+    tav_int: N = N::ZERO,
+    int: N = N::ZERO,
     // Bonus
-    let lb_rate: Vec<f64> = col_to_vec_f64(&df, "lb_rate"); // Read only
-    let mut lb_tav_withdrawal_review: Vec<f64> = col_to_vec_f64(&df, "lb_tav_withdrawal_review");
-    let mut lb_flag: Vec<bool> = col_to_vec_bool(&df, "lb_flag");
-    let mut lb: Vec<f64> = col_to_vec_f64(&df, "lb");
-    let mut sb_rate: Vec<f64> = col_to_vec_f64(&df, "sb_rate");
-    let mut sb_tav_withdrawal_review: Vec<f64> = col_to_vec_f64(&df, "sb_tav_withdrawal_review");
-    let mut sb_flag: Vec<bool> = col_to_vec_bool(&df, "sb_flag");
-    let mut sb: Vec<f64> = col_to_vec_f64(&df, "sb");
-    let mut bonus: Vec<f64> = col_to_vec_f64(&df, "bonus");
+    lb_tav_withdrawal_review: N = N::ZERO,
+    lb_flag: bool = false,
+    lb: N = N::ZERO,
+    sb_rate: N = N::ZERO,
+    sb_tav_withdrawal_review: N = N::ZERO,
+    sb_flag: bool = false,
+    sb: N = N::ZERO,
+    bonus: N = N::ZERO,
     // End values
-    let mut end_si: Vec<f64> = col_to_vec_f64(&df, "end_si");
-    let mut end_tav: Vec<f64> = col_to_vec_f64(&df, "end_tav");
-    let mut end_eav: Vec<f64> = col_to_vec_f64(&df, "end_eav");
-    let mut end_pav: Vec<f64> = col_to_vec_f64(&df, "end_pav");
+    end_si: N = N::ZERO,
+    end_eav: N = N::ZERO,
+    end_tav: N = N::ZERO,
+    end_pav: N = N::ZERO,
+}
+
+// TAV withdrawn in the review window of `period` years ending at row i.
+// The window reaches back into the history when it starts before the first projected year.
+fn _tav_withdrawal_review<N: Num>(
+    c: &VariedConsts<N>,
+    tav_withdrawal: &[N],
+    i: usize,
+    period: usize,
+) -> N {
+    let history = &c.tav_withdrawal_history;
+    let past_years = period.saturating_sub(i + 1).min(history.len());
+    let projected: N = tav_withdrawal[(i + 1).saturating_sub(period)..=i]
+        .iter()
+        .copied()
+        .sum();
+    projected + history[history.len() - past_years..].iter().copied().sum()
+}
 
-    for i in 0..=(vec_length - 1 as usize) {
+// All the calculation for a single year must be completed then move on to another year
+fn _varied_row_by_row_cf<N: Num>(
+    input: &VariedInputs<N>,
+    c: &VariedConsts<N>,
+    s: &mut VariedState<N>,
+) -> PolarsResult<()> {
+    let round = |quantity, value| c.rounding.round(quantity, value);
+    let flag = N::from_flag;
+    let (zero, one) = (N::ZERO, N::ONE);
+    let (twelve, thousand) = (N::from_input(12.0)?, N::from_input(1000.0)?);
+    for i in 0..s.start_si.len() {
         // Update start values
         if i > 0 {
-            start_si[i] = end_si[i - 1];
-            start_eav[i] = end_eav[i - 1];
-            start_tav[i] = end_tav[i - 1];
-            cont_flag[i] = deduction_flag[i - 1];
+            s.start_si[i] = s.end_si[i - 1];
+            s.start_eav[i] = s.end_eav[i - 1];
+            s.start_tav[i] = s.end_tav[i - 1];
+            s.cont_flag[i] = s.deduction_flag[i - 1];
         } else {
-            start_si[i] = base.si;
-            cont_flag[i] = true;
+            s.start_si[i] = c.start_si;
+            s.start_eav[i] = c.start_eav;
+            s.start_tav[i] = c.start_tav;
+            s.cont_flag[i] = true;
         }
 
-        start_pav[i] = start_tav[i] + start_eav[i];
+        s.start_pav[i] = s.start_tav[i] + s.start_eav[i];
 
         // Surrender value
-        srr_val[i] = f64::max(start_tav[i] - srr_chrg[i], 0.0) + start_eav[i];
+        s.srr_val[i] = (s.start_tav[i] - input.srr_chrg[i]).max(zero) + s.start_eav[i];
 
         // Benefits
-        ben[i] = if base.death_tpd_option == DeathTPDBenefitEnum::A {
-            f64::max(start_si[i], start_pav[i]) * juvenile_lien_rate[i]
+        s.ben[i] = if c.option_a {
+            s.start_si[i].max(s.start_pav[i]) * input.juvenile_lien_rate[i]
         } else {
-            start_si[i] * juvenile_lien_rate[i]
+            s.start_si[i] * input.juvenile_lien_rate[i]
         };
 
         // Accidental benefit -
-        acc_ben[i] =
-            start_si[i] * (base.acc_ben_coeff as f64) * (acc_ben_term_flag[i] as u8 as f64);
+        s.acc_ben[i] = s.start_si[i] * c.acc_ben_coeff * flag(input.acc_ben_term_flag[i]);
 
         // Withdrawal
         let (wdrl, eav_wdrl, tav_wdrl, end_si_v, wdrl_log) = calculate_withdrawal(
-            withdrawal_input[i],
-            start_eav[i],
-            start_tav[i],
-            start_si[i],
-            base,
-        )?;
-        withdrawal[i] = wdrl;
-        eav_withdrawal[i] = eav_wdrl;
-        tav_withdrawal[i] = tav_wdrl;
-        end_si[i] = end_si_v;
-        withdrawal_log[i] = wdrl_log.to_string();
+            input.withdrawal_input[i],
+            s.start_eav[i],
+            s.start_tav[i],
+            s.start_si[i],
+            &c.withdrawal_limits,
+        );
+        s.withdrawal[i] = wdrl;
+        s.eav_withdrawal[i] = eav_wdrl;
+        s.tav_withdrawal[i] = tav_wdrl;
+        s.end_si[i] = end_si_v;
+        s.withdrawal_log[i] = wdrl_log;
 
         // EM Load
-        unrounded_em_load[i] = base.extra_prem_rate()?
-            * base.load.em_load
-            * (em_load_term_flag[i] as u8 as f64)
-            * (risk_flag[i] as u8 as f64)
-            * ben[i]
-            / 1000.0
-            / (1.0 - ep_alloc_chrg_rate[i]);
+        s.unrounded_em_load[i] = (c.extra_prem_rate
+            * c.em_load
+            * flag(input.em_load_term_flag[i])
+            * flag(input.risk_flag[i])
+            * s.ben[i]
+            / thousand)
+            .try_div(one - input.ep_alloc_chrg_rate[i])?;
 
-        em_load[i] = (unrounded_em_load[i] / 1000.0).ceil() * 1000.0;
+        s.em_load[i] = round(Load, s.unrounded_em_load[i])?;
 
         // PM Load
-        unrounded_pm_load[i] = (base.load.pm_load as f64)
-            * (pm_load_term_flag[i] as u8 as f64)
-            * (risk_flag[i] as u8 as f64)
-            * ben[i]
-            / 1000.0
-            / (1.0 - ep_alloc_chrg_rate[i]);
+        s.unrounded_pm_load[i] =
+            (c.pm_load * flag(input.pm_load_term_flag[i]) * flag(input.risk_flag[i]) * s.ben[i]
+                / thousand)
+                .try_div(one - input.ep_alloc_chrg_rate[i])?;
 
-        pm_load[i] = (unrounded_pm_load[i] / 1000.0).ceil() * 1000.0;
+        s.pm_load[i] = round(Load, s.unrounded_pm_load[i])?;
 
         // Load
-        load[i] = em_load[i] + pm_load[i];
-        load_alloc_chrg[i] = load[i] * ep_alloc_chrg_rate[i];
-        load_alloc[i] = load[i] - load_alloc_chrg[i];
+        s.load[i] = s.em_load[i] + s.pm_load[i];
+        s.load_alloc_chrg[i] = s.load[i] * input.ep_alloc_chrg_rate[i];
+        s.load_alloc[i] = s.load[i] - s.load_alloc_chrg[i];
 
         // Obtain total allocation charge and allocation
-        alloc_chrg[i] = load_alloc_chrg[i] + ep_alloc_chrg[i] + tp_alloc_chrg[i];
-        alloc[i] = load_alloc[i] + ep_alloc[i] + tp_alloc[i];
+        s.alloc_chrg[i] = s.load_alloc_chrg[i] + input.ep_alloc_chrg[i] + input.tp_alloc_chrg[i];
+        s.alloc[i] = s.load_alloc[i] + input.ep_alloc[i] + input.tp_alloc[i];
 
         // Account values after withdrawal and allocation
-        eav_after_wdrl_and_alloc[i] =
-            start_eav[i] - eav_withdrawal[i] + ep_alloc[i] + load_alloc[i];
-        tav_after_wdrl_and_alloc[i] = start_tav[i] - tav_withdrawal[i] + tp_alloc[i];
-        pav_after_wdrl_and_alloc[i] = eav_after_wdrl_and_alloc[i] + tav_after_wdrl_and_alloc[i];
+        s.eav_after_wdrl_and_alloc[i] =
+            s.start_eav[i] - s.eav_withdrawal[i] + input.ep_alloc[i] + s.load_alloc[i];
+        s.tav_after_wdrl_and_alloc[i] = s.start_tav[i] - s.tav_withdrawal[i] + input.tp_alloc[i];
+        s.pav_after_wdrl_and_alloc[i] =
+            s.eav_after_wdrl_and_alloc[i] + s.tav_after_wdrl_and_alloc[i];
 
         //SAR
-        sar[i] = if base.death_tpd_option == DeathTPDBenefitEnum::A {
-            f64::max(ben[i] - start_pav[i], 0.0)
+        s.sar[i] = if c.option_a {
+            (s.ben[i] - s.start_pav[i]).max(zero)
         } else {
-            ben[i]
+            s.ben[i]
         };
 
         // COI
-        standard_coi[i] = sar[i] * coi_rate[i];
-        em_load_coi[i] = base.load.em_load * standard_coi[i] * (risk_flag[i] as u8 as f64);
-        pm_load_coi[i] = (base.load.pm_load as f64) * sar[i] * (risk_flag[i] as u8 as f64) / 1000.0;
-        acc_coi[i] = acc_ben[i] * base.acc_coi_rate()? * (acc_ben_term_flag[i] as u8 as f64) * 12.0;
-        coi[i] = standard_coi[i] + em_load_coi[i] + pm_load_coi[i] + acc_coi[i];
+        let risk_flag = flag(input.risk_flag[i]);
+        s.standard_coi[i] = s.sar[i] * input.coi_rate[i];
+        s.em_load_coi[i] = c.em_load * s.standard_coi[i] * risk_flag;
+        s.pm_load_coi[i] = c.pm_load * s.sar[i] * risk_flag / thousand;
+        s.acc_coi[i] = s.acc_ben[i] * c.acc_coi_rate * flag(input.acc_ben_term_flag[i]) * twelve;
+        s.coi[i] = round(
+            Coi,
+            s.standard_coi[i] + s.em_load_coi[i] + s.pm_load_coi[i] + s.acc_coi[i],
+        )?;
 
         // Deduction
-        plan_nom_deduction[i] = coi[i] + admin_chrg[i] * 12.0; // Sum of 12 installments
-        plan_deduction[i] = plan_nom_deduction[i] * due_a_n1_m12[i]; // Convert 12 installments to present value
-        deduction_flag[i] = plan_deduction[i] <= pav_after_wdrl_and_alloc[i];
+        s.plan_nom_deduction[i] = s.coi[i] + input.admin_chrg[i] * twelve; // Sum of 12 installments
+        s.plan_deduction[i] = round(Deduction, s.plan_nom_deduction[i] * input.due_a_n1_m12[i])?; // Convert 12 installments to present value
+        s.deduction_flag[i] = s.plan_deduction[i] <= s.pav_after_wdrl_and_alloc[i];
 
-        if !deduction_flag[i] {
+        if !s.deduction_flag[i] {
             break;
         }
 
-        nom_deduction[i] = plan_nom_deduction[i] * (deduction_flag[i] as u8 as f64);
-        deduction[i] = plan_deduction[i] * (deduction_flag[i] as u8 as f64);
-        tav_deduction[i] = f64::min(tav_after_wdrl_and_alloc[i], deduction[i]);
-        eav_deduction[i] = deduction[i] - tav_deduction[i];
+        s.nom_deduction[i] = s.plan_nom_deduction[i];
+        s.deduction[i] = s.plan_deduction[i];
+        s.tav_deduction[i] = s.tav_after_wdrl_and_alloc[i].min(s.deduction[i]);
+        s.eav_deduction[i] = s.deduction[i] - s.tav_deduction[i];
 
         // Interest
-        eav_int[i] = (eav_after_wdrl_and_alloc[i] - eav_deduction[i]) * annual_int_rate[i];
-        tav_int[i] = (tav_after_wdrl_and_alloc[i] - tav_deduction[i]) * annual_int_rate[i];
-        int[i] = eav_int[i] + tav_int[i];
+        s.eav_int[i] = round(
+            Interest,
+            (s.eav_after_wdrl_and_alloc[i] - s.eav_deduction[i]) * input.annual_int_rate[i],
+        )?;
+        s.tav_int[i] = round(
+            Interest,
+            (s.tav_after_wdrl_and_alloc[i] - s.tav_deduction[i]) * input.annual_int_rate[i],
+        )?;
+        s.int[i] = s.eav_int[i] + s.tav_int[i];
 
         // Loyalty bonus
-        if lb_rate[i] > 0.0 {
-            let window = base.lb_review_period()? as usize;
-            let start_idx = usize::max(i + 1 - window, 0);
-            let lb_tav_wdrl_sum = tav_withdrawal[start_idx..=i].iter().sum();
-            lb_tav_withdrawal_review[i] = lb_tav_wdrl_sum;
-            lb_flag[i] = lb_tav_wdrl_sum == 0.0;
+        if input.lb_rate[i] > zero {
+            let lb_tav_wdrl_sum =
+                _tav_withdrawal_review(c, &s.tav_withdrawal, i, c.lb_review_period);
+            s.lb_tav_withdrawal_review[i] = lb_tav_wdrl_sum;
+            s.lb_flag[i] = lb_tav_wdrl_sum == zero;
         }
-        lb[i] = lb_rate[i] * base.modal_tp_tuple()?.0 * (lb_flag[i] as u8 as f64);
+        s.lb[i] = round(Bonus, input.lb_rate[i] * c.annual_tp * flag(s.lb_flag[i]))?;
 
         // Special bonus
-        sb_rate[i] = get_sb_rate(i, start_si[i], base.id);
-
-        if sb_rate[i] > 0.0 {
-            let window = base.sb_review_period()? as usize;
-            let start_idx = usize::max(i + 1 - window, 0);
-            let sb_tav_wdrl_sum = tav_withdrawal[start_idx..=i].iter().sum();
-            sb_tav_withdrawal_review[i] = sb_tav_wdrl_sum;
-            sb_flag[i] = sb_tav_wdrl_sum == 0.0;
+        // Rows start at the first projected policy year
+        let year = c.first_year + i as i32;
+        let sb_rule = find_sb_rule(&c.sb_rules, year, s.start_si[i].to_output()?);
+        s.sb_rate[i] = match sb_rule {
+            Some(rule) => N::from_input(rule.rate)?,
+            None => zero,
+        };
+
+        if let Some(rule) = sb_rule
+            && rule.rate > 0.0
+        {
+            let sb_tav_wdrl_sum =
+                _tav_withdrawal_review(c, &s.tav_withdrawal, i, rule.review_period as usize);
+            s.sb_tav_withdrawal_review[i] = sb_tav_wdrl_sum;
+            s.sb_flag[i] = sb_tav_wdrl_sum == zero;
         }
-        sb[i] = sb_rate[i] * base.modal_tp_tuple()?.0 * (sb_flag[i] as u8 as f64);
-        bonus[i] = lb[i] + sb[i];
+        s.sb[i] = round(Bonus, s.sb_rate[i] * c.annual_tp * flag(s.sb_flag[i]))?;
+        s.bonus[i] = s.lb[i] + s.sb[i];
 
         // End values
-        end_eav[i] = eav_after_wdrl_and_alloc[i] - eav_deduction[i] + eav_int[i];
-        end_tav[i] = tav_after_wdrl_and_alloc[i] - tav_deduction[i] + tav_int[i] + bonus[i];
-        end_pav[i] = end_eav[i] + end_tav[i];
+        s.end_eav[i] = round(
+            AccountValue,
+            s.eav_after_wdrl_and_alloc[i] - s.eav_deduction[i] + s.eav_int[i],
+        )?;
+        s.end_tav[i] = round(
+            AccountValue,
+            s.tav_after_wdrl_and_alloc[i] - s.tav_deduction[i] + s.tav_int[i] + s.bonus[i],
+        )?;
+        s.end_pav[i] = s.end_eav[i] + s.end_tav[i];
     }
+    Ok(())
+}
+
+// Adds the premium columns to the frame and returns the kernel columns
+fn _varied_kernel<N: Num>(
+    df: &mut DataFrame,
+    base: &Base,
+    rules: &ProductRules,
+    columns: Option<&[&str]>,
+) -> PolarsResult<Vec<Column>> {
+    let consts = VariedConsts::<N>::new(base, rules)?;
+    let prem = VariedPrem::new(df, &consts)?;
+    let input = VariedInputs::new(df, &prem)?;
+    let mut state = VariedState::new(df.height());
+    _varied_row_by_row_cf(&input, &consts, &mut state)?;
+    prem.insert_into(df)?;
+    state.into_columns(columns)
+}
+
+fn _varied(
+    run: &ScenarioRun,
+    lf: LazyFrame,
+    base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
+    columns: Option<&[&str]>,
+) -> PolarsResult<LazyFrame> {
+    let mut df = _varied_init(run, lf, base, rules)?;
+    df.as_single_chunk_par();
 
-    // Update the dataframe with the updated vectors
-    let lf = update_df_with_vectors!(
-        df,
-        [
-            // Start SI
-            cont_flag,
-            start_si,
-            start_tav,
-            start_eav,
-            start_pav,
-            // Surrender
-            srr_val,
-            // Benefirs
-            ben,
-            acc_ben_term_flag,
-            acc_ben,
-            // Withdrawal
-            withdrawal,
-            eav_withdrawal,
-            tav_withdrawal,
-            withdrawal_log,
-            // Premium load
-            unrounded_pm_load,
-            unrounded_em_load,
-            pm_load,
-            em_load,
-            load,
-            load_alloc_chrg,
-            load_alloc,
-            // Allocation charge
-            alloc_chrg,
-            alloc,
-            // Account values after withdrawal and allocation
-            eav_after_wdrl_and_alloc,
-            tav_after_wdrl_and_alloc,
-            pav_after_wdrl_and_alloc,
-            // COI
-            sar,
-            standard_coi,
-            acc_coi,
-            em_load_coi,
-            pm_load_coi,
-            coi,
-            // Deduction
-            plan_nom_deduction,
-            plan_deduction,
-            deduction_flag,
-            nom_deduction,
-            deduction,
-            tav_deduction,
-            eav_deduction,
-            // Interest
-            eav_int,
-            tav_int,
-            int,
-            // Loyalty bonus
-            lb_tav_withdrawal_review,
-            lb_flag,
-            lb,
-            // Special Bonus
-            sb_rate,
-            sb_tav_withdrawal_review,
-            sb_flag,
-            sb,
-            bonus,
-            // End values
-            end_si,
-            end_tav,
-            end_eav,
-            end_pav,
-        ]
-    );
-
-    // Filter out rows where deduction flag is false
-    //Ok(lf.filter(col("deduction_flag").eq(lit(true))))
-    Ok(lf)
+    // Only the requested columns are materialised
+    let kernel_columns = match config.arithmetic {
+        ArithmeticEnum::Float => _varied_kernel::<f64>(&mut df, base, rules, columns)?,
+        ArithmeticEnum::Decimal => _varied_kernel::<Decimal>(&mut df, base, rules, columns)?,
+    };
+    let df = match columns {
+        None => df.hstack(&kernel_columns)?,
+        Some(columns) => {
+            let init_columns: Vec<&str> = columns
+                .iter()
+                .copied()
+                .filter(|name| df.get_column_index(name).is_some())
+                .collect();
+            df.select(init_columns)?
+                .hstack(&kernel_columns)?
+                .select(columns.iter().copied())?
+        }
+    };
+    Ok(df.lazy())
 }
 
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
 pub fn varied(
-    scenario: (IntRateScenarioEnum, RiskTypeEnum, PremTermScenarioEnum),
+    scenario: Scenario,
+    lf: LazyFrame,
+    base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
+) -> PolarsResult<LazyFrame> {
+    _varied(
+        &ScenarioRun::standard(scenario, base, rules, config)?,
+        lf,
+        base,
+        rules,
+        config,
+        None,
+    )
+}
+
+/// Same as `varied`, but only the listed columns are materialised, in the listed order.
+pub fn varied_select(
+    scenario: Scenario,
+    lf: LazyFrame,
+    base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
+    columns: &[&str],
+) -> PolarsResult<LazyFrame> {
+    _varied(
+        &ScenarioRun::standard(scenario, base, rules, config)?,
+        lf,
+        base,
+        rules,
+        config,
+        Some(columns),
+    )
+}
+
+/// Projects a resolved scenario, standard or custom.
+pub fn varied_run(
+    run: &ScenarioRun,
     lf: LazyFrame,
     base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
 ) -> PolarsResult<LazyFrame> {
-    let lf = _varied_init(scenario, lf, base)?;
-    _varied_row_by_row_cf(lf, base)
+    _varied(run, lf, base, rules, config, None)
 }
 // -----------------------------------------------------------------------------
 // UNIT TESTS
@@ -477,38 +700,277 @@ pub fn varied(
 mod tests {
     use super::*;
     use crate::cashflows::base_cf::fixed::fixed;
-    use crate::helpers::read_json_struct;
+    use crate::database::shock::AssumptionShock;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::helpers::{df_to_csv, read_json_struct};
     use crate::structs::policy::Policy;
-    use std::fs::File;
 
     #[test]
     fn test_fn_fixed() {
         // Generate result DataFrame from fixed function
         let json_path = "src/cashflows/test_data/uvl01_policy.json";
         let policy = read_json_struct::<Policy>(json_path).unwrap();
-        let lf = fixed(&policy.base).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let lf = fixed(&policy.base, &rules, &RunConfig::default()).unwrap();
+        // result_varied.csv holds the Subrisk run since the baseline, which checks the loads too
         let scenario = (
             IntRateScenarioEnum::High,
-            RiskTypeEnum::Standard,
+            RiskTypeEnum::Subrisk,
             PremTermScenarioEnum::PolicyTerm,
         );
-        let df = varied(scenario, lf, &policy.base)
+        let df = varied(scenario, lf, &policy.base, &rules, &RunConfig::default())
             .unwrap()
             .collect()
             .unwrap();
 
-        // Export result df to csv to cross check in Excel file
+        // Exported to cross check in Excel file; refresh after an intended change with:
+        // UPDATE_RESULTS=1 cargo test test_fn_fixed
         let export_path = "src/cashflows/test_data/result_varied.csv";
-        let mut export_file = File::create(export_path).unwrap();
-        CsvWriter::new(&mut export_file)
-            .finish(&mut df.clone())
+        let result = df_to_csv(&df).unwrap();
+        if std::env::var("UPDATE_RESULTS").is_ok() {
+            std::fs::write(export_path, &result).unwrap();
+        }
+
+        // Result in Excel file-varied sheet: Correct.
+        let expected = std::fs::read_to_string(export_path).unwrap();
+        assert!(
+            result == expected,
+            "DataFrames do not match {}.",
+            export_path
+        );
+    }
+
+    #[test]
+    fn test_fn_varied_int_rate_curve() {
+        // Rates and the deduction factor follow the curve row by row
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        policy.base.int_rate_curve = serde_json::from_str(
+            r#"{"basis": "PolicyYear", "high": [{"from": 1, "rate": 0.08}, {"from": 4, "rate": 0.06}]}"#,
+        )
+        .unwrap();
+        let lf = fixed(&policy.base, &rules, &RunConfig::default()).unwrap();
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        let df = varied(scenario, lf, &policy.base, &rules, &RunConfig::default())
+            .unwrap()
+            .collect()
             .unwrap();
 
-        // Import Excel range as df to check within Rust program
-        // TODO: Replace with actual Excel import logic
+        let rate = col_to_vec_f64(&df, "annual_int_rate");
+        let factor = col_to_vec_f64(&df, "due_a_n1_m12");
+        assert_eq!((rate[2], rate[3]), (0.08, 0.06));
+        assert_eq!(factor[0], factor[2]);
+        assert!(factor[3] > factor[2]);
+    }
 
-        // Assert that the result matches the expected DataFrame
-        // Result in Excel file-varied sheet: Correct.
-        assert!(true, "DataFrames do not match.");
+    #[test]
+    fn test_fn_varied_select() {
+        // Selected columns come out in the listed order with the same values as a full run
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let lf = fixed(&policy.base, &rules, &RunConfig::default()).unwrap();
+        let scenario = (
+            IntRateScenarioEnum::Low,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        );
+        let columns = ["end_pav", "year", "withdrawal_log"];
+        let selected = varied_select(
+            scenario,
+            lf.clone(),
+            &policy.base,
+            &rules,
+            &RunConfig::default(),
+            &columns,
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+        let full = varied(scenario, lf, &policy.base, &rules, &RunConfig::default())
+            .unwrap()
+            .select(columns.map(col))
+            .collect()
+            .unwrap();
+
+        let names: Vec<&str> = selected.get_column_names_str();
+        assert_eq!(names, columns);
+        assert!(selected.equals(&full));
+    }
+
+    #[test]
+    fn test_fn_varied_max_admin_chrg() {
+        // The guaranteed maximum caps the admin charge and never raises it
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let lf = fixed(&policy.base, &rules, &RunConfig::default()).unwrap();
+        let scenario = (
+            IntRateScenarioEnum::Guaranteed,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        let admin_chrg = |max_admin_chrg| {
+            let mut run =
+                ScenarioRun::standard(scenario, &policy.base, &rules, &RunConfig::default())
+                    .unwrap();
+            run.max_admin_chrg = Some(max_admin_chrg);
+            let df = varied_run(
+                &run,
+                lf.clone(),
+                &policy.base,
+                &rules,
+                &RunConfig::default(),
+            )
+            .unwrap()
+            .collect()
+            .unwrap();
+            col_to_vec_f64(&df, "admin_chrg")
+        };
+        assert!(admin_chrg(20_000.0).iter().all(|a| *a == 20_000.0));
+        assert!(admin_chrg(30_000.0).iter().all(|a| *a == 25_000.0));
+    }
+
+    #[test]
+    fn test_fn_varied_max_admin_chrg_02() {
+        // No product ships a guaranteed maximum yet, so the cap is set on the Guaranteed run.
+        // Capped at today's 25,000, a +20% admin charge shock projects as the unshocked basis.
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let scenario = (
+            IntRateScenarioEnum::Guaranteed,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        );
+        let shocked = RunConfig {
+            shock: AssumptionShock {
+                admin_chrg_mult: 1.2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let project = |config: &RunConfig, max_admin_chrg| {
+            let mut run = ScenarioRun::standard(scenario, &policy.base, &rules, config).unwrap();
+            assert_eq!(run.max_admin_chrg, None);
+            run.max_admin_chrg = max_admin_chrg;
+            let lf = fixed(&policy.base, &rules, config).unwrap();
+            varied_run(&run, lf, &policy.base, &rules, config)
+                .unwrap()
+                .collect()
+                .unwrap()
+        };
+        let basis = project(&RunConfig::default(), None);
+        let uncapped = project(&shocked, None);
+        let capped = project(&shocked, Some(25_000.0));
+
+        assert!(
+            col_to_vec_f64(&uncapped, "admin_chrg")
+                .iter()
+                .all(|a| *a == 30_000.0)
+        );
+        assert!(
+            col_to_vec_f64(&capped, "admin_chrg")
+                .iter()
+                .all(|a| *a == 25_000.0)
+        );
+        for column in ["deduction", "end_pav"] {
+            assert_eq!(
+                col_to_vec_f64(&capped, column),
+                col_to_vec_f64(&basis, column),
+                "{}",
+                column
+            );
+            assert_ne!(
+                col_to_vec_f64(&uncapped, column),
+                col_to_vec_f64(&basis, column),
+                "{}",
+                column
+            );
+        }
+    }
+
+    #[test]
+    fn test_fn_varied_full_alloc_chrg() {
+        // Loads are grossed up by 1 - EP allocation charge, which a 100% charge cannot be
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Subrisk,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        for arithmetic in [ArithmeticEnum::Float, ArithmeticEnum::Decimal] {
+            let config = RunConfig {
+                shock: AssumptionShock {
+                    ep_alloc_chrg_rate_mult: 100.0, // Capped at a 100% charge
+                    ..Default::default()
+                },
+                arithmetic,
+            };
+            let lf = fixed(&policy.base, &rules, &config).unwrap();
+            let result =
+                varied(scenario, lf, &policy.base, &rules, &config).and_then(|lf| lf.collect());
+            let err = result.unwrap_err().to_string();
+            assert!(
+                err.contains("cannot be divided by 0"),
+                "{:?}: {}",
+                arithmetic,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_fn_varied_decimal() {
+        // Rates are read exactly as the table stores them, and every step runs in decimals
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
+        let lf = fixed(&policy.base, &rules, &RunConfig::default()).unwrap();
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        let float = varied(
+            scenario,
+            lf.clone(),
+            &policy.base,
+            &rules,
+            &RunConfig::default(),
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+        let config = RunConfig {
+            arithmetic: ArithmeticEnum::Decimal,
+            ..Default::default()
+        };
+        let decimal = varied(scenario, lf, &policy.base, &rules, &config)
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        assert_eq!(col_to_vec_f64(&decimal, "coi_rate")[0], 0.00187999999999999);
+        // 101,000,000 SAR at 0.00187999999999999, exactly
+        assert_eq!(
+            col_to_vec_f64(&decimal, "standard_coi")[0],
+            189_879.999_999_998_98
+        );
+
+        for column in ["due_a_n1_m12", "tp_alloc", "end_pav"] {
+            let float_values = col_to_vec_f64(&float, column);
+            let decimal_values = col_to_vec_f64(&decimal, column);
+            for (f, d) in float_values.iter().zip(&decimal_values) {
+                approx::assert_relative_eq!(f, d, max_relative = 1e-9);
+            }
+        }
     }
 }
@@ -0,0 +1,83 @@
+use crate::cashflows::base_cf::base_cf;
+use crate::cashflows::scenario::{RunConfig, ScenarioSpec};
+use crate::enums::StatusEnum;
+use crate::structs::policy::Policy;
+use polars::prelude::*;
+use rayon::prelude::*;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Projects the base of each policy, e.g. the policies of an in-force extract.
+///
+/// Rows keep the policy order, each led by its `policy_id`. Policies that are not in force have
+/// nothing left to project and are skipped. The first failing policy returns its error, prefixed by its id.
+pub fn batch_cf(
+    policies: &[Policy],
+    spec: &ScenarioSpec,
+    config: &RunConfig,
+) -> PolarsResult<LazyFrame> {
+    let lfs = policies
+        .par_iter()
+        .filter(|policy| policy.base.status == StatusEnum::Inforce)
+        .map(|policy| {
+            let df = base_cf(&policy.base, spec, config)
+                .and_then(|lf| lf.collect())
+                .map_err(|e| {
+                    let err_msg = format!("Policy {}: {}", policy.id, e);
+                    PolarsError::ComputeError(err_msg.into())
+                })?;
+            Ok(df
+                .lazy()
+                .select([lit(policy.id.as_str()).alias("policy_id"), all()]))
+        })
+        .collect::<PolarsResult<Vec<LazyFrame>>>()?;
+
+    if lfs.is_empty() {
+        return Ok(DataFrame::empty().lazy());
+    }
+    concat(lfs, Default::default())
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::structs::extract::{ExtractMapping, read_extract};
+
+    #[test]
+    fn test_fn_batch_cf_01() {
+        // The accepted policies of an extract project straight away; lapsed ones are skipped
+        let path = "src/structs/test_data/inforce_extract.csv";
+        let mut policies = read_extract(path, &ExtractMapping::default())
+            .unwrap()
+            .policies;
+        let mut lapsed = policies[0].clone();
+        lapsed.id = "P005".to_string();
+        lapsed.base.status = StatusEnum::Lapsed;
+        policies.push(lapsed);
+
+        let spec = ScenarioSpec::Standard(vec![(
+            IntRateScenarioEnum::Low,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        )]);
+        let df = batch_cf(&policies, &spec, &RunConfig::default())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let ids = df.column("policy_id").unwrap().str().unwrap();
+        assert_eq!(ids.n_unique().unwrap(), 1);
+        assert_eq!(ids.get(0), Some("P001"));
+        assert_eq!(
+            df.height() as i32,
+            policies[0]
+                .base
+                .term(&policies[0].base.rules().unwrap())
+                .unwrap()
+        );
+    }
+}
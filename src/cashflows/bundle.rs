@@ -0,0 +1,175 @@
+use crate::cashflows::policy_cf::policy_cf;
+use crate::cashflows::scenario::{RunConfig, ScenarioSpec};
+use crate::database::table::{TableEnum, table_hash};
+use crate::helpers::df_to_json_records;
+use crate::structs::policy::Policy;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use strum::IntoEnumIterator;
+
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// Rate tables holding rows of the base or a rider, keyed by table name
+fn _table_hashes(policy: &Policy) -> PolarsResult<BTreeMap<String, String>> {
+    let mut products = vec![policy.base.id.as_ref()];
+    products.extend(policy.rider.iter().flatten().map(|rider| rider.id.as_ref()));
+
+    let mut hashes = BTreeMap::new();
+    for table in TableEnum::iter() {
+        if let Some(hash) = table_hash(&table, &products)? {
+            hashes.insert(table.as_ref().to_string(), hash);
+        }
+    }
+    Ok(hashes)
+}
+
+// Projection and summary of the illustration as JSON records
+fn _output(
+    policy: &Policy,
+    spec: &ScenarioSpec,
+    config: &RunConfig,
+) -> PolarsResult<(serde_json::Value, serde_json::Value)> {
+    policy.validate_fields().map_err(|issues| {
+        let err_msg = serde_json::to_string(&issues).unwrap_or_default();
+        PolarsError::ComputeError(format!("Invalid policy: {}", err_msg).into())
+    })?;
+    let cf = policy_cf(policy, spec, config)?;
+    Ok((
+        df_to_json_records(&cf.projection.collect()?)?,
+        df_to_json_records(&cf.summary.collect()?)?,
+    ))
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Everything needed to reproduce an issued illustration.
+// The run config is kept apart because the policy JSON does not carry it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    pub engine_version: String,
+    pub policy: Policy,
+    pub config: RunConfig,
+    pub scenarios: ScenarioSpec,
+    pub table_hashes: BTreeMap<String, String>, // SHA-256 of the rows of the policy's products
+    pub projection: serde_json::Value,
+    pub summary: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplayReport {
+    pub engine_version: String, // Version that replayed the bundle
+    pub engine_version_matches: bool,
+    pub changed_tables: Vec<String>, // Tables added, removed or changed since the bundle
+    pub projection_matches: bool,
+    pub summary_matches: bool,
+}
+
+impl ReplayReport {
+    pub fn is_identical(&self) -> bool {
+        self.engine_version_matches
+            && self.changed_tables.is_empty()
+            && self.projection_matches
+            && self.summary_matches
+    }
+}
+
+/// Illustrates a validated policy and records what it was computed from.
+///
+/// The bundle serializes to JSON and can be replayed with `replay`.
+pub fn bundle(policy: &Policy, spec: &ScenarioSpec, config: &RunConfig) -> PolarsResult<Bundle> {
+    let (projection, summary) = _output(policy, spec, config)?;
+    Ok(Bundle {
+        engine_version: ENGINE_VERSION.to_string(),
+        policy: policy.clone(),
+        config: config.clone(),
+        scenarios: spec.clone(),
+        table_hashes: _table_hashes(policy)?,
+        projection,
+        summary,
+    })
+}
+
+/// Recomputes the illustration of a bundle with this engine and the current rate tables.
+///
+/// The report lists every difference: engine version, rate tables and output.
+/// Output is compared value by value, so it is identical only when every cell is.
+pub fn replay(bundle: &Bundle) -> PolarsResult<ReplayReport> {
+    let table_hashes = _table_hashes(&bundle.policy)?;
+    let tables: BTreeSet<&String> = bundle
+        .table_hashes
+        .keys()
+        .chain(table_hashes.keys())
+        .collect();
+    let changed_tables = tables
+        .into_iter()
+        .filter(|table| bundle.table_hashes.get(*table) != table_hashes.get(*table))
+        .cloned()
+        .collect();
+
+    let (projection, summary) = _output(&bundle.policy, &bundle.scenarios, &bundle.config)?;
+    Ok(ReplayReport {
+        engine_version: ENGINE_VERSION.to_string(),
+        engine_version_matches: bundle.engine_version == ENGINE_VERSION,
+        changed_tables,
+        projection_matches: projection == bundle.projection,
+        summary_matches: summary == bundle.summary,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::helpers::read_json_struct;
+
+    fn issued() -> Bundle {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let mut config = RunConfig::default();
+        config.shock.coi_rate_mult = 1.1;
+        let spec = ScenarioSpec::Standard(vec![(
+            IntRateScenarioEnum::Low,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        )]);
+        bundle(&policy, &spec, &config).unwrap()
+    }
+
+    #[test]
+    fn test_fn_replay_01() {
+        // A bundle read back from JSON replays to the same output
+        let json = serde_json::to_string(&issued()).unwrap();
+        let bundle: Bundle = serde_json::from_str(&json).unwrap();
+        assert!(bundle.table_hashes.contains_key("ul_coi_rate"));
+
+        let report = replay(&bundle).unwrap();
+        assert!(report.is_identical(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_fn_replay_02() {
+        // Changed rates and output are reported, not hidden
+        let mut bundle = issued();
+        bundle
+            .table_hashes
+            .insert("ul_coi_rate".to_string(), "0".repeat(64));
+        bundle.summary[0]["lapse_year"] = serde_json::json!(1);
+        bundle.config = RunConfig::default();
+
+        let report = replay(&bundle).unwrap();
+        assert!(!report.is_identical());
+        assert!(report.engine_version_matches);
+        assert_eq!(report.changed_tables, ["ul_coi_rate"]);
+        // Without the COI shock the projection differs too
+        assert!(!report.projection_matches);
+        assert!(!report.summary_matches);
+    }
+}
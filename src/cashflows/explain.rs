@@ -0,0 +1,477 @@
+use crate::cashflows::base_cf::scenario_cf_select;
+use crate::cashflows::scenario::{RunConfig, Scenario};
+use crate::database::rounding::RoundingRules;
+use crate::enums::{DeathTPDBenefitEnum, RoundedQuantityEnum};
+use crate::structs::base::Base;
+use polars::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+// Projection columns the explanations are read from
+const COLUMNS: [&str; 33] = [
+    "year",
+    "cont_flag",
+    "deduction_flag",
+    "start_si",
+    "start_pav",
+    "juvenile_lien_rate",
+    "ben",
+    "em_load_term_flag",
+    "pm_load_term_flag",
+    "ep_alloc_chrg_rate",
+    "em_load",
+    "pm_load",
+    "sar",
+    "coi_rate",
+    "standard_coi",
+    "risk_flag",
+    "em_load_coi",
+    "pm_load_coi",
+    "acc_ben",
+    "acc_ben_term_flag",
+    "acc_coi",
+    "coi",
+    "admin_chrg",
+    "due_a_n1_m12",
+    "plan_deduction",
+    "lb_rate",
+    "lb_tav_withdrawal_review",
+    "lb_flag",
+    "lb",
+    "sb_rate",
+    "sb_tav_withdrawal_review",
+    "sb_flag",
+    "sb",
+];
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// One projection row, with flags read as 1 or 0 so they show in the inputs like any factor
+struct Row<'a> {
+    df: &'a DataFrame,
+    i: usize,
+}
+
+impl Row<'_> {
+    fn get(&self, name: &str) -> PolarsResult<f64> {
+        let column = self.df.column(name)?;
+        let value = match column.dtype() {
+            DataType::Boolean => column.bool()?.get(self.i).map(|v| v as u8 as f64),
+            _ => column.cast(&DataType::Float64)?.f64()?.get(self.i),
+        };
+        Ok(value.unwrap_or(0.0))
+    }
+}
+
+// Rounding step of a formula, e.g. ", rounded Up to 1000"
+fn _rounding_text(rounding: &RoundingRules, quantity: RoundedQuantityEnum) -> String {
+    match rounding.rule(quantity) {
+        Some(rule) => format!(", rounded {} to {}", rule.direction.as_ref(), rule.unit),
+        None => String::new(),
+    }
+}
+
+fn _explanation(
+    row: &Row,
+    quantity: &str,
+    formula: String,
+    inputs: &[(&str, f64)],
+) -> PolarsResult<Explanation> {
+    Ok(Explanation {
+        year: row.get("year")? as i32,
+        quantity: quantity.to_string(),
+        value: row.get(quantity)?,
+        formula,
+        inputs: inputs
+            .iter()
+            .map(|(name, value)| ExplainInput {
+                name: name.to_string(),
+                value: *value,
+            })
+            .collect(),
+    })
+}
+
+fn _explain_row(
+    row: &Row,
+    base: &Base,
+    rounding: &RoundingRules,
+    (annual_tp, extra_prem_rate): (f64, f64),
+) -> PolarsResult<Vec<Explanation>> {
+    let option_a = base.death_tpd_option == DeathTPDBenefitEnum::A;
+    let v = |name: &str| row.get(name);
+    let mut result = Vec::new();
+
+    // Benefit and sum at risk
+    result.push(if option_a {
+        _explanation(
+            row,
+            "ben",
+            "max(start_si, start_pav) * juvenile_lien_rate".to_string(),
+            &[
+                ("start_si", v("start_si")?),
+                ("start_pav", v("start_pav")?),
+                ("juvenile_lien_rate", v("juvenile_lien_rate")?),
+            ],
+        )?
+    } else {
+        _explanation(
+            row,
+            "ben",
+            "start_si * juvenile_lien_rate".to_string(),
+            &[
+                ("start_si", v("start_si")?),
+                ("juvenile_lien_rate", v("juvenile_lien_rate")?),
+            ],
+        )?
+    });
+    result.push(if option_a {
+        _explanation(
+            row,
+            "sar",
+            "max(ben - start_pav, 0)".to_string(),
+            &[("ben", v("ben")?), ("start_pav", v("start_pav")?)],
+        )?
+    } else {
+        _explanation(row, "sar", "ben".to_string(), &[("ben", v("ben")?)])?
+    });
+
+    // Load premiums, grossed up for the EP allocation charge
+    let load_rounding = _rounding_text(rounding, RoundedQuantityEnum::Load);
+    result.push(_explanation(
+        row,
+        "em_load",
+        format!(
+            "extra_prem_rate * load.em_load * em_load_term_flag * risk_flag * ben / 1000 / (1 - ep_alloc_chrg_rate){}",
+            load_rounding
+        ),
+        &[
+            ("extra_prem_rate", extra_prem_rate),
+            ("load.em_load", base.load.em_load),
+            ("em_load_term_flag", v("em_load_term_flag")?),
+            ("risk_flag", v("risk_flag")?),
+            ("ben", v("ben")?),
+            ("ep_alloc_chrg_rate", v("ep_alloc_chrg_rate")?),
+        ],
+    )?);
+    result.push(_explanation(
+        row,
+        "pm_load",
+        format!(
+            "load.pm_load * pm_load_term_flag * risk_flag * ben / 1000 / (1 - ep_alloc_chrg_rate){}",
+            load_rounding
+        ),
+        &[
+            ("load.pm_load", base.load.pm_load as f64),
+            ("pm_load_term_flag", v("pm_load_term_flag")?),
+            ("risk_flag", v("risk_flag")?),
+            ("ben", v("ben")?),
+            ("ep_alloc_chrg_rate", v("ep_alloc_chrg_rate")?),
+        ],
+    )?);
+
+    // COI
+    result.push(_explanation(
+        row,
+        "standard_coi",
+        "sar * coi_rate".to_string(),
+        &[("sar", v("sar")?), ("coi_rate", v("coi_rate")?)],
+    )?);
+    result.push(_explanation(
+        row,
+        "em_load_coi",
+        "load.em_load * standard_coi * risk_flag".to_string(),
+        &[
+            ("load.em_load", base.load.em_load),
+            ("standard_coi", v("standard_coi")?),
+            ("risk_flag", v("risk_flag")?),
+        ],
+    )?);
+    result.push(_explanation(
+        row,
+        "pm_load_coi",
+        "load.pm_load * sar * risk_flag / 1000".to_string(),
+        &[
+            ("load.pm_load", base.load.pm_load as f64),
+            ("sar", v("sar")?),
+            ("risk_flag", v("risk_flag")?),
+        ],
+    )?);
+    result.push(_explanation(
+        row,
+        "acc_coi",
+        "acc_ben * acc_coi_rate * acc_ben_term_flag * 12".to_string(),
+        &[
+            ("acc_ben", v("acc_ben")?),
+            ("acc_coi_rate", base.acc_coi_rate()?),
+            ("acc_ben_term_flag", v("acc_ben_term_flag")?),
+        ],
+    )?);
+    result.push(_explanation(
+        row,
+        "coi",
+        format!(
+            "standard_coi + em_load_coi + pm_load_coi + acc_coi{}",
+            _rounding_text(rounding, RoundedQuantityEnum::Coi)
+        ),
+        &[
+            ("standard_coi", v("standard_coi")?),
+            ("em_load_coi", v("em_load_coi")?),
+            ("pm_load_coi", v("pm_load_coi")?),
+            ("acc_coi", v("acc_coi")?),
+        ],
+    )?);
+
+    // Deduction: 12 monthly installments at their present value
+    result.push(_explanation(
+        row,
+        "plan_deduction",
+        format!(
+            "(coi + admin_chrg * 12) * due_a_n1_m12{}",
+            _rounding_text(rounding, RoundedQuantityEnum::Deduction)
+        ),
+        &[
+            ("coi", v("coi")?),
+            ("admin_chrg", v("admin_chrg")?),
+            ("due_a_n1_m12", v("due_a_n1_m12")?),
+        ],
+    )?);
+
+    // Bonuses are not paid in the year the deduction is not covered
+    if row.get("deduction_flag")? == 1.0 {
+        let bonus_rounding = _rounding_text(rounding, RoundedQuantityEnum::Bonus);
+        result.push(_explanation(
+            row,
+            "lb",
+            format!("lb_rate * annual_tp * lb_flag{}", bonus_rounding),
+            &[
+                ("lb_rate", v("lb_rate")?),
+                ("annual_tp", annual_tp),
+                ("lb_flag", v("lb_flag")?),
+                ("lb_tav_withdrawal_review", v("lb_tav_withdrawal_review")?),
+            ],
+        )?);
+        result.push(_explanation(
+            row,
+            "sb",
+            format!("sb_rate * annual_tp * sb_flag{}", bonus_rounding),
+            &[
+                ("sb_rate", v("sb_rate")?),
+                ("annual_tp", annual_tp),
+                ("sb_flag", v("sb_flag")?),
+                ("sb_tav_withdrawal_review", v("sb_tav_withdrawal_review")?),
+            ],
+        )?);
+    }
+    Ok(result)
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExplainInput {
+    pub name: String,
+    pub value: f64,
+}
+
+// How one output cell was derived: the projection value, its formula and the values it used.
+// A flag input is 1 when set and 0 when not; lb_flag and sb_flag are set when no TAV was
+// withdrawn in the review window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation {
+    pub year: i32,
+    pub quantity: String, // Output column
+    pub value: f64,
+    pub formula: String,
+    pub inputs: Vec<ExplainInput>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| format!("{} = {}", input.name, input.value))
+            .collect();
+        write!(
+            f,
+            "Year {} {} = {} = {} ({})",
+            self.year,
+            self.quantity,
+            self.formula,
+            self.value,
+            inputs.join(", ")
+        )
+    }
+}
+
+/// Projects a single scenario and explains `ben`, the load premiums, `sar`, the COI and its parts,
+/// `plan_deduction`, `lb` and `sb` of every projected year, in year order.
+///
+/// Values are read back from the projection, so each explanation shows exactly what was projected.
+/// Years after a lapse are not explained.
+pub fn explain(
+    scenario: Scenario,
+    base: &Base,
+    config: &RunConfig,
+) -> PolarsResult<Vec<Explanation>> {
+    let rules = base.rules()?;
+    let df = scenario_cf_select(scenario, base, &rules, config, &COLUMNS)?.collect()?;
+    let consts = (
+        base.modal_tp_tuple(&rules)?.0,
+        base.extra_prem_rate(&rules)?,
+    );
+
+    let mut result = Vec::new();
+    for i in 0..df.height() {
+        let row = Row { df: &df, i };
+        if row.get("cont_flag")? == 0.0 {
+            break;
+        }
+        result.extend(_explain_row(&row, base, &rules.rounding, consts)?);
+    }
+    Ok(result)
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::helpers::read_json_struct;
+    use crate::structs::policy::Policy;
+
+    #[test]
+    fn test_fn_explain_01() {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        let explanations = explain(scenario, &policy.base, &RunConfig::default()).unwrap();
+
+        // Year 7 COI ties out to its inputs
+        let coi = explanations
+            .iter()
+            .find(|e| e.year == 7 && e.quantity == "standard_coi")
+            .unwrap();
+        assert_eq!(coi.formula, "sar * coi_rate");
+        let input = |name: &str| coi.inputs.iter().find(|i| i.name == name).unwrap().value;
+        assert_eq!(coi.value, input("sar") * input("coi_rate"));
+
+        let df = scenario_cf_select(
+            scenario,
+            &policy.base,
+            &policy.base.rules().unwrap(),
+            &RunConfig::default(),
+            &["year", "standard_coi"],
+        )
+        .unwrap()
+        .filter(col("year").eq(lit(7)))
+        .collect()
+        .unwrap();
+        let projected = df.column("standard_coi").unwrap().f64().unwrap().get(0);
+        assert_eq!(Some(coi.value), projected);
+
+        // Every projected year explains the same quantities
+        let years = explanations.iter().filter(|e| e.quantity == "ben").count();
+        assert_eq!(explanations.len(), years * 12);
+
+        let json = serde_json::to_value(coi).unwrap();
+        assert_eq!(json["inputs"][1]["name"], "coi_rate");
+        assert!(
+            coi.to_string()
+                .starts_with("Year 7 standard_coi = sar * coi_rate = ")
+        );
+    }
+
+    // Value of an explanation worked out again from its inputs, the way the kernel does
+    fn recompute(e: &Explanation, rounding: &RoundingRules) -> f64 {
+        use RoundedQuantityEnum::*;
+
+        let x = |name: &str| match e.inputs.iter().find(|i| i.name == name) {
+            Some(input) => input.value,
+            None => panic!("Year {} {} has no input {}", e.year, e.quantity, name),
+        };
+        let round = |quantity, value: f64| rounding.round(quantity, value).unwrap();
+        let option_a = e.formula.starts_with("max");
+        match e.quantity.as_str() {
+            "ben" if option_a => x("start_si").max(x("start_pav")) * x("juvenile_lien_rate"),
+            "ben" => x("start_si") * x("juvenile_lien_rate"),
+            "em_load" => round(
+                Load,
+                x("extra_prem_rate")
+                    * x("load.em_load")
+                    * x("em_load_term_flag")
+                    * x("risk_flag")
+                    * x("ben")
+                    / 1000.0
+                    / (1.0 - x("ep_alloc_chrg_rate")),
+            ),
+            "pm_load" => round(
+                Load,
+                x("load.pm_load") * x("pm_load_term_flag") * x("risk_flag") * x("ben")
+                    / 1000.0
+                    / (1.0 - x("ep_alloc_chrg_rate")),
+            ),
+            "sar" if option_a => (x("ben") - x("start_pav")).max(0.0),
+            "sar" => x("ben"),
+            "standard_coi" => x("sar") * x("coi_rate"),
+            "em_load_coi" => x("load.em_load") * x("standard_coi") * x("risk_flag"),
+            "pm_load_coi" => x("load.pm_load") * x("sar") * x("risk_flag") / 1000.0,
+            "acc_coi" => x("acc_ben") * x("acc_coi_rate") * x("acc_ben_term_flag") * 12.0,
+            "coi" => round(
+                Coi,
+                x("standard_coi") + x("em_load_coi") + x("pm_load_coi") + x("acc_coi"),
+            ),
+            "plan_deduction" => round(
+                Deduction,
+                (x("coi") + x("admin_chrg") * 12.0) * x("due_a_n1_m12"),
+            ),
+            "lb" => round(Bonus, x("lb_rate") * x("annual_tp") * x("lb_flag")),
+            "sb" => round(Bonus, x("sb_rate") * x("annual_tp") * x("sb_flag")),
+            other => panic!("{} is not recomputed", other),
+        }
+    }
+
+    #[test]
+    fn test_fn_explain_02() {
+        // Every explained value of every year follows from its inputs, and its formula only names inputs.
+        // The subrisk scenario charges the loads.
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rounding = policy.base.rules().unwrap().rounding;
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Subrisk,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        for option in [DeathTPDBenefitEnum::A, DeathTPDBenefitEnum::B] {
+            policy.base.death_tpd_option = option;
+            let explanations = explain(scenario, &policy.base, &RunConfig::default()).unwrap();
+            assert!(
+                explanations
+                    .iter()
+                    .any(|e| e.quantity == "em_load" && e.value > 0.0)
+            );
+
+            for e in &explanations {
+                assert_eq!(e.value, recompute(e, &rounding), "{}", e);
+
+                let terms = e.formula.split(", rounded").next().unwrap();
+                for name in terms.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.')) {
+                    let is_input = e.inputs.iter().any(|i| i.name == name);
+                    let is_literal =
+                        name.is_empty() || name == "max" || name.parse::<f64>().is_ok();
+                    assert!(is_input || is_literal, "{} in {}", name, e);
+                }
+            }
+        }
+    }
+}
@@ -1,20 +1,9 @@
-use crate::enums::{DeathTPDBenefitEnum, ULEnum};
+use crate::database::product::ProductRules;
+use crate::enums::{DeathTPDBenefitEnum, WithdrawalLogEnum};
+use crate::num::Num;
 use crate::structs::base::Base;
 use polars::prelude::*;
 
-#[macro_export]
-macro_rules! update_df_with_vectors {
-    ($df:expr, [ $( $vec:ident ),* $(,)? ]) => {{
-        let mut lf = $df.clone().lazy();
-        $(
-            let name = stringify!($vec);
-            let expr = lit(Series::new(name.into(), $vec.clone())).alias(name);
-            lf = lf.with_column(expr);
-        )*
-        lf
-    }};
-}
-
 // -----------------------------------------------------------------------------
 // Below functions are used selectively with pre-defined column names of known types.
 // Hence we can safely assume the column types and panic if they do not match.
@@ -71,28 +60,41 @@ fn _assert_column_dtype(col: &Column, expected_dtype: &DataType) {
 }
 
 // -----------------------------------------------------------------------------
-pub fn calculate_withdrawal(
-    amount: f64,
-    eav: f64,
-    tav: f64,
-    si: f64,
-    base: &Base,
-) -> PolarsResult<(f64, f64, f64, f64, &str)> {
+// Limits checked on every withdrawal, looked up once per projection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WithdrawalLimits<N> {
+    pub min_pav_after_withdrawal: N,
+    pub min_si: N,
+    pub death_tpd_option: DeathTPDBenefitEnum,
+}
+
+impl<N: Num> WithdrawalLimits<N> {
+    pub fn new(base: &Base, rules: &ProductRules) -> PolarsResult<Self> {
+        Ok(Self {
+            min_pav_after_withdrawal: N::from_input(base.min_pav_after_withdrawal(rules)?)?,
+            min_si: N::from_input(base.min_si()?)?,
+            death_tpd_option: base.death_tpd_option,
+        })
+    }
+}
+
+pub fn calculate_withdrawal<N: Num>(
+    amount: N,
+    eav: N,
+    tav: N,
+    si: N,
+    limits: &WithdrawalLimits<N>,
+) -> (N, N, N, N, &'static str) {
+    let zero = N::ZERO;
     // No withdrawal
-    if amount == 0.0 {
-        return Ok((0.0, 0.0, 0.0, si, "No withdrawal."));
+    if amount == zero {
+        return (zero, zero, zero, si, WithdrawalLogEnum::NoWithdrawal.into());
     }
 
     // When withdrawal amount exceeds current policy account value
     let pav = tav + eav;
     if amount > pav {
-        return Ok((
-            0.0,
-            0.0,
-            0.0,
-            si,
-            "Withdrawal amount exceeds policy account value.",
-        ));
+        return (zero, zero, zero, si, WithdrawalLogEnum::ExceedsPav.into());
     }
     // When policy account value after withdrawal is lower than acceptable limit
     let pav_withdrawal = amount;
@@ -103,53 +105,26 @@ pub fn calculate_withdrawal(
     let end_tav = tav - tav_withdrawal;
     let end_pav = end_eav + end_tav;
 
-    if end_pav < base.min_pav_after_withdrawal()? {
-        return Ok((
-            0.0,
-            0.0,
-            0.0,
-            si,
-            "After withdrawal, end PAV is below acceptable limit.",
-        ));
+    if end_pav < limits.min_pav_after_withdrawal {
+        return (zero, zero, zero, si, WithdrawalLogEnum::BelowMinPav.into());
     }
 
     // When sum insured after withdrawal is lower than acceptable limit
-    let end_si = if base.death_tpd_option == DeathTPDBenefitEnum::A {
+    let end_si = if limits.death_tpd_option == DeathTPDBenefitEnum::A {
         si
     } else {
-        f64::max(pav, si) - amount
+        pav.max(si) - amount
     };
 
-    if end_si < base.min_si()? {
-        return Ok((
-            0.0,
-            0.0,
-            0.0,
-            si,
-            "After withdrawal, end SI is below acceptable limit.",
-        ));
+    if end_si < limits.min_si {
+        return (zero, zero, zero, si, WithdrawalLogEnum::BelowMinSi.into());
     }
 
-    Ok((
+    (
         pav_withdrawal,
         eav_withdrawal,
         tav_withdrawal,
         end_si,
-        "Successfully withdraw.",
-    ))
-}
-
-pub fn get_sb_rate(year: usize, si: f64, product: ULEnum) -> f64 {
-    match product {
-        ULEnum::UVL02 | ULEnum::UVL03 => match (year, si) {
-            (10, si) if si >= 1_000_000_000.0 => 0.4,
-            (20, si) if si >= 1_000_000_000.0 => 1.2,
-            (10, si) if si >= 500_000_000.0 => 0.2,
-            (20, si) if si >= 500_000_000.0 => 0.6,
-            (10, _) => 0.2,
-            (20, _) => 0.6,
-            _ => 0.0,
-        },
-        _ => 0.0,
-    }
+        WithdrawalLogEnum::Success.into(),
+    )
 }
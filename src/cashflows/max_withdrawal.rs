@@ -0,0 +1,236 @@
+use crate::cashflows::base_cf::scenario_cf_select;
+use crate::cashflows::helpers::{col_to_vec_bool, col_to_vec_f64, col_to_vec_string};
+use crate::cashflows::scenario::{RunConfig, Scenario};
+use crate::database::product::ProductRules;
+use crate::enums::{WithdrawalConstraintEnum, WithdrawalLogEnum};
+use crate::structs::base::Base;
+use crate::structs::withdrawal::{Withdrawal, WithdrawalPlan};
+use polars::prelude::*;
+use serde::Serialize;
+use std::str::FromStr;
+
+// Withdrawal amounts are searched on a 1,000 grid, the same rounding as the premiums
+const AMOUNT_STEP: f64 = 1_000.0;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn _with_level_withdrawal(base: &Base, from: i32, to: i32, amount: f64) -> Base {
+    let mut base = base.clone();
+    base.withdrawal_plan = Some(WithdrawalPlan(vec![Withdrawal { from, to, amount }]));
+    base
+}
+
+// Projects the scenario with the level withdrawal and returns the first constraint it breaks, if any
+fn _breached_constraint(
+    base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
+    scenario: Scenario,
+    (from, to): (i32, i32),
+    amount: f64,
+    keep_bonus: bool,
+) -> PolarsResult<Option<WithdrawalConstraintEnum>> {
+    let base = _with_level_withdrawal(base, from, to, amount);
+    let columns = [
+        "year",
+        "withdrawal_log",
+        "deduction_flag",
+        "lb_rate",
+        "lb_flag",
+        "sb_rate",
+        "sb_flag",
+    ];
+    let df = scenario_cf_select(scenario, &base, rules, config, &columns)?.collect()?;
+
+    let year: Vec<i32> = df.column("year")?.i32()?.into_no_null_iter().collect();
+    let withdrawal_log = col_to_vec_string(&df, "withdrawal_log");
+    let deduction_flag = col_to_vec_bool(&df, "deduction_flag");
+    let lb_rate = col_to_vec_f64(&df, "lb_rate");
+    let lb_flag = col_to_vec_bool(&df, "lb_flag");
+    let sb_rate = col_to_vec_f64(&df, "sb_rate");
+    let sb_flag = col_to_vec_bool(&df, "sb_flag");
+
+    // Within a year the withdrawal is taken before deductions, then bonuses are credited
+    for i in 0..df.height() {
+        if (from..=to).contains(&year[i]) {
+            let constraint = match WithdrawalLogEnum::from_str(&withdrawal_log[i]) {
+                Ok(WithdrawalLogEnum::ExceedsPav) => {
+                    Some(WithdrawalConstraintEnum::PolicyAccountValue)
+                }
+                Ok(WithdrawalLogEnum::BelowMinPav) => {
+                    Some(WithdrawalConstraintEnum::MinPavAfterWithdrawal)
+                }
+                Ok(WithdrawalLogEnum::BelowMinSi) => Some(WithdrawalConstraintEnum::MinSi),
+                _ => None,
+            };
+            if constraint.is_some() {
+                return Ok(constraint);
+            }
+        }
+
+        if !deduction_flag[i] {
+            return Ok(Some(WithdrawalConstraintEnum::Lapse));
+        }
+
+        let bonus_forfeited =
+            (lb_rate[i] > 0.0 && !lb_flag[i]) || (sb_rate[i] > 0.0 && !sb_flag[i]);
+        if keep_bonus && bonus_forfeited {
+            return Ok(Some(WithdrawalConstraintEnum::BonusForfeiture));
+        }
+    }
+    Ok(None)
+}
+
+fn _max_withdrawal(
+    base: &Base,
+    rules: &ProductRules,
+    config: &RunConfig,
+    scenario: Scenario,
+    years: (i32, i32),
+    keep_bonus: bool,
+) -> PolarsResult<MaxWithdrawal> {
+    let result = |amount: f64, binding: WithdrawalConstraintEnum| MaxWithdrawal {
+        scenario,
+        amount,
+        binding,
+    };
+
+    // The policy cannot sustain any withdrawal
+    if let Some(binding) =
+        _breached_constraint(base, rules, config, scenario, years, 0.0, keep_bonus)?
+    {
+        return Ok(result(0.0, binding));
+    }
+
+    // Nothing above the account value at the first withdrawal year can be taken
+    let df = scenario_cf_select(scenario, base, rules, config, &["year", "start_pav"])?
+        .filter(col("year").eq(lit(years.0)))
+        .select([col("start_pav")])
+        .collect()?;
+    let start_pav = df.column("start_pav")?.f64()?.get(0).unwrap_or(0.0);
+
+    let (mut ok, mut breached) = (0_i64, (start_pav / AMOUNT_STEP).floor() as i64 + 1);
+    let mut binding = _breached_constraint(
+        base,
+        rules,
+        config,
+        scenario,
+        years,
+        breached as f64 * AMOUNT_STEP,
+        keep_bonus,
+    )?
+    .unwrap_or(WithdrawalConstraintEnum::PolicyAccountValue);
+
+    while breached - ok > 1 {
+        let mid = ok + (breached - ok) / 2;
+        let amount = mid as f64 * AMOUNT_STEP;
+        match _breached_constraint(base, rules, config, scenario, years, amount, keep_bonus)? {
+            Some(constraint) => (breached, binding) = (mid, constraint),
+            None => ok = mid,
+        }
+    }
+    Ok(result(ok as f64 * AMOUNT_STEP, binding))
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MaxWithdrawal {
+    pub scenario: Scenario,
+    pub amount: f64,                       // Level annual withdrawal
+    pub binding: WithdrawalConstraintEnum, // Constraint broken by the next 1,000 up
+}
+
+/// Largest level annual withdrawal from policy year `from` to `to` that each scenario can sustain.
+///
+/// The level withdrawal replaces the base withdrawal plan and runs through the full
+/// `calculate_withdrawal` rules, so `min_pav_after_withdrawal` and `min_si` apply every year.
+/// The policy must not lapse before maturity.
+/// Withdrawals from TAV forfeit loyalty and special bonuses, which lowers later account values.
+/// With `keep_bonus`, a forfeited bonus is also treated as a breach.
+pub fn max_withdrawal(
+    base: &Base,
+    from: i32,
+    to: i32,
+    scenarios: &[Scenario],
+    keep_bonus: bool,
+    config: &RunConfig,
+) -> PolarsResult<Vec<MaxWithdrawal>> {
+    let rules = base.rules()?;
+    let term = base.term(&rules)?;
+    let start_year = base.withdrawal_start_year()?;
+    if from < start_year || from > to || to > term {
+        let err_msg = format!(
+            "Withdrawal years {} to {} must lie between year {} and the policy term {}.",
+            from, to, start_year, term
+        );
+        return Err(PolarsError::ComputeError(err_msg.into()));
+    }
+
+    scenarios
+        .iter()
+        .map(|scenario| _max_withdrawal(base, &rules, config, *scenario, (from, to), keep_bonus))
+        .collect()
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::helpers::read_json_struct;
+    use crate::structs::policy::Policy;
+
+    fn base() -> Base {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        read_json_struct::<Policy>(json_path).unwrap().base
+    }
+
+    #[test]
+    fn test_fn_max_withdrawal_01() {
+        let base = base();
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        );
+        let years = (20, 25);
+        let config = RunConfig::default();
+        let rules = base.rules().unwrap();
+        let result = max_withdrawal(&base, years.0, years.1, &[scenario], false, &config).unwrap();
+        let max = &result[0];
+        assert!(max.amount > 0.0);
+        assert_eq!(max.amount % AMOUNT_STEP, 0.0);
+
+        // The maximum is sustainable and the next step up breaks the reported constraint
+        let at_max =
+            _breached_constraint(&base, &rules, &config, scenario, years, max.amount, false)
+                .unwrap();
+        assert_eq!(at_max, None);
+        let above = _breached_constraint(
+            &base,
+            &rules,
+            &config,
+            scenario,
+            years,
+            max.amount + AMOUNT_STEP,
+            false,
+        );
+        assert_eq!(above.unwrap(), Some(max.binding));
+    }
+
+    #[test]
+    fn test_fn_max_withdrawal_02() {
+        // Withdrawals are not allowed in the first policy year
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        );
+        assert!(max_withdrawal(&base(), 1, 5, &[scenario], false, &RunConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::cashflows::base_cf::base_cf;
+use crate::cashflows::scenario::{RunConfig, ScenarioSpec};
+use crate::database::product::ProductRules;
+use crate::enums::StatusEnum;
+use crate::structs::policy::Policy;
+use crate::structs::rider::Rider;
+use polars::prelude::*;
+
+const SCENARIO_COLS: [&str; 3] = ["int_rate_scenario", "risk_scenario", "term_scenario"];
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// In-force rider with its last year of cover and death benefit, and its rules loaded once for the yearly premiums
+struct RiderCover<'a> {
+    rider: &'a Rider,
+    rules: ProductRules,
+    expiry_year: i32,
+    death_ben: f64,
+}
+
+fn _rider_covers(policy: &Policy, base_term: i32) -> PolarsResult<Vec<RiderCover<'_>>> {
+    // A rider cannot outlive the base
+    policy
+        .rider
+        .iter()
+        .flatten()
+        .filter(|rider| rider.status == StatusEnum::Inforce)
+        .map(|rider| {
+            let rules = rider.rules()?;
+            Ok(RiderCover {
+                rider,
+                expiry_year: i32::min(rider.term(&rules)?, base_term),
+                death_ben: rider.death_benefit()?,
+                rules,
+            })
+        })
+        .collect()
+}
+
+// Value of the rider in the years it covers, zero afterwards
+fn _while_covered(cover: &RiderCover, value: f64) -> Expr {
+    when(col("year").lt_eq(lit(cover.expiry_year)))
+        .then(lit(value))
+        .otherwise(lit(0.0))
+}
+
+// Premium of all riders by policy year; loads run off, so it is rated year by year
+fn _rider_prem_lf(covers: &[RiderCover], base_term: i32) -> PolarsResult<LazyFrame> {
+    let years: Vec<i32> = (1..=base_term).collect();
+    let mut prems = vec![0.0; years.len()];
+    for cover in covers {
+        for year in 1..=cover.expiry_year {
+            prems[(year - 1) as usize] += cover.rider.annual_prem(year, &cover.rules)?;
+        }
+    }
+    let df = df![
+        "year" => years,
+        "rider_prem" => prems
+    ]?;
+    Ok(df.lazy())
+}
+
+fn _projection(
+    lf: LazyFrame,
+    policy: &Policy,
+    covers: &[RiderCover],
+    rider_prem_lf: LazyFrame,
+) -> LazyFrame {
+    // Premiums are paid and riders cover only while the base is in force at the start of the year
+    let in_force = col("cont_flag").cast(DataType::Float64);
+
+    // Base insured first, then the rider insureds in rider order
+    let mut insured_ids = vec![policy.base.insured.id.as_str()];
+    for cover in covers {
+        if !insured_ids.contains(&cover.rider.insured.id.as_str()) {
+            insured_ids.push(cover.rider.insured.id.as_str());
+        }
+    }
+    let death_bens: Vec<Expr> = insured_ids
+        .iter()
+        .map(|id| {
+            let base_ben = if *id == policy.base.insured.id {
+                col("ben")
+            } else {
+                lit(0.0)
+            };
+            let ben = covers
+                .iter()
+                .filter(|cover| cover.rider.insured.id == *id)
+                .fold(base_ben, |acc, cover| {
+                    acc + _while_covered(cover, cover.death_ben)
+                });
+            (ben * in_force.clone()).alias(format!("death_ben_{}", id))
+        })
+        .collect();
+
+    // Riders in their last year of cover, grouped by expiry year
+    let mut expiring: Vec<(i32, Vec<&str>)> = Vec::new();
+    for cover in covers {
+        match expiring
+            .iter_mut()
+            .find(|(year, _)| *year == cover.expiry_year)
+        {
+            Some((_, ids)) => ids.push(cover.rider.id.as_ref()),
+            None => expiring.push((cover.expiry_year, vec![cover.rider.id.as_ref()])),
+        }
+    }
+    let expiring_riders = expiring.iter().fold(lit(""), |acc, (year, ids)| {
+        when(col("year").eq(lit(*year)))
+            .then(lit(ids.join(",")))
+            .otherwise(acc)
+    });
+
+    let mut exprs = vec![
+        cols(SCENARIO_COLS),
+        col("year"),
+        ((col("tp") + col("ep")) * in_force.clone()).alias("base_prem"),
+        (col("rider_prem") * in_force).alias("rider_prem"),
+    ];
+    exprs.extend(death_bens);
+    exprs.push(expiring_riders.alias("expiring_riders"));
+
+    lf.join(
+        rider_prem_lf,
+        [col("year")],
+        [col("year")],
+        JoinArgs::new(JoinType::Left),
+    )
+    .select(exprs)
+    .with_column((col("base_prem") + col("rider_prem")).alias("total_prem"))
+}
+
+fn _summary(projection: LazyFrame, base_lf: LazyFrame, term: i32) -> LazyFrame {
+    let keys: Vec<Expr> = SCENARIO_COLS.iter().map(|c| col(*c)).collect();
+
+    // Rows after a lapse keep the default deduction_flag of false, so the first one is the lapse year
+    let benefits = base_lf.group_by_stable(keys.clone()).agg([
+        col("withdrawal").sum().alias("total_withdrawal"),
+        col("end_pav")
+            .filter(col("year").eq(lit(term)).and(col("deduction_flag")))
+            .sum()
+            .alias("maturity_value"),
+        col("year")
+            .filter(col("deduction_flag").not())
+            .min()
+            .alias("lapse_year"),
+    ]);
+
+    projection
+        .group_by_stable(keys.clone())
+        .agg([
+            col("base_prem").sum().alias("total_base_prem"),
+            col("rider_prem").sum().alias("total_rider_prem"),
+            col("total_prem").sum().alias("total_prem"),
+        ])
+        .join(benefits, keys.clone(), keys, JoinArgs::new(JoinType::Left))
+        .with_column((col("total_withdrawal") + col("maturity_value")).alias("total_benefit"))
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+pub struct PolicyCf {
+    // One row per scenario and year
+    pub projection: LazyFrame,
+    // One row per scenario
+    pub summary: LazyFrame,
+}
+
+/// Consolidated illustration of the base and its in-force riders.
+///
+/// The projection shows the base, rider and total premium, and the death benefit of each insured
+/// in `death_ben_<insured id>` columns. `expiring_riders` lists the riders in their last year of cover.
+/// The summary totals the premium outlay and the benefits paid: withdrawals plus the account value
+/// at maturity. A null lapse year means the scenario stays in force to maturity.
+pub fn policy_cf(
+    policy: &Policy,
+    spec: &ScenarioSpec,
+    config: &RunConfig,
+) -> PolarsResult<PolicyCf> {
+    let base_term = policy.base.term(&policy.base.rules()?)?;
+    let covers = _rider_covers(policy, base_term)?;
+    let base_lf = base_cf(&policy.base, spec, config)?.collect()?.lazy();
+
+    let rider_prem_lf = _rider_prem_lf(&covers, base_term)?;
+
+    let projection = _projection(base_lf.clone(), policy, &covers, rider_prem_lf)
+        .collect()?
+        .lazy();
+    let summary = _summary(projection.clone(), base_lf, base_term);
+    Ok(PolicyCf {
+        projection,
+        summary,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::helpers::read_json_struct;
+
+    fn policy() -> Policy {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        read_json_struct::<Policy>(json_path).unwrap()
+    }
+
+    fn spec() -> ScenarioSpec {
+        ScenarioSpec::Standard(vec![(
+            IntRateScenarioEnum::Low,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        )])
+    }
+
+    #[test]
+    fn test_fn_policy_cf_01() {
+        // Without riders the totals are the base premium and benefits
+        let mut policy = policy();
+        policy.rider = None;
+        let result = policy_cf(&policy, &spec(), &RunConfig::default()).unwrap();
+        let projection = result.projection.collect().unwrap();
+        let summary = result.summary.collect().unwrap();
+
+        let death_ben_col = format!("death_ben_{}", policy.base.insured.id);
+        assert!(projection.column(&death_ben_col).is_ok());
+        let first_prem = projection
+            .column("total_prem")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0);
+        let rules = policy.base.rules().unwrap();
+        let (tp, ep) = (
+            policy.base.modal_tp_tuple(&rules).unwrap().0,
+            policy.base.modal_ep_tuple(&rules).unwrap().0,
+        );
+        assert_eq!(first_prem, Some(tp + ep));
+
+        let total_prem = summary.column("total_prem").unwrap().f64().unwrap().get(0);
+        let total_base_prem = summary
+            .column("total_base_prem")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0);
+        assert_eq!(summary.height(), 1);
+        assert_eq!(total_prem, total_base_prem);
+    }
+
+    #[test]
+    fn test_fn_policy_cf_02() {
+        // Riders off cover are left out
+        let mut policy = policy();
+        for rider in policy.rider.iter_mut().flatten() {
+            rider.status = StatusEnum::Lapsed;
+        }
+        let result = policy_cf(&policy, &spec(), &RunConfig::default()).unwrap();
+        let projection = result.projection.collect().unwrap();
+        let rider_prem = projection.column("rider_prem").unwrap().f64().unwrap();
+        assert_eq!(rider_prem.max(), Some(0.0));
+    }
+
+    #[test]
+    fn test_fn_policy_cf_03() {
+        // Riders add their premium while covered, and ADD01 adds its SI to the death benefit
+        let policy = policy();
+        let result = policy_cf(&policy, &spec(), &RunConfig::default()).unwrap();
+        let projection = result.projection.collect().unwrap();
+
+        // First year from the Tariff sheet, with the EM load on the rate and the PM load per 1000 SI,
+        // rounded up to 1000: female insureds aged 0 (PPD01) and 31 (the others)
+        let expected_prem = 54_000.0 // PPD01: 1.18 * 12,000 * 1.25 + 3 * 12,000
+            + 54_000.0 // ADD01: 1.4 * 12,000 * 1.75 + 2 * 12,000
+            + 190_000.0 // CIR02: 3.2 * 25,000 * 1.75 + 2 * 25,000
+            + 620_000.0 // SUP01: 354,237 * 1.75
+            + 630_000.0 // HOP02: plan 4 of 360,000 * 1.75
+            + 1_046_000.0; // CIR01: 2.27 * 175,000 * 1.75 + 2 * 175,000
+        let rider_prem = projection.column("rider_prem").unwrap().f64().unwrap();
+        assert_eq!(rider_prem.get(0), Some(expected_prem));
+
+        // ADD01 of insured 3 covers to age 61, i.e. 30 years
+        let expiring = projection.column("expiring_riders").unwrap().str().unwrap();
+        assert_eq!(expiring.get(29), Some("ADD01"));
+        let death_ben = projection.column("death_ben_0000000000000000003").unwrap();
+        let death_ben = death_ben.f64().unwrap();
+        assert_eq!(death_ben.get(29), Some(12_000_000.0));
+        assert_eq!(death_ben.get(30), Some(0.0));
+    }
+}
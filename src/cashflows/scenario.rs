@@ -0,0 +1,238 @@
+use crate::database::product::ProductRules;
+use crate::database::shock::AssumptionShock;
+use crate::enums::{ArithmeticEnum, IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+use crate::structs::base::Base;
+use crate::structs::int_rate_curve::{IntRatePoint, points_validation};
+use garde::Validate;
+use itertools::iproduct;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+pub type Scenario = (IntRateScenarioEnum, RiskTypeEnum, PremTermScenarioEnum);
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Scenario with its own crediting rate path and premium terms.
+// The name labels both the int_rate_scenario and term_scenario columns.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CustomScenario {
+    #[garde(length(min = 1))]
+    pub name: String,
+
+    #[garde(custom(points_validation))]
+    #[garde(dive)]
+    pub int_rate: Vec<IntRatePoint>, // Declared rates by policy year, floored like High and Low
+
+    #[garde(skip)]
+    pub risk: RiskTypeEnum,
+
+    #[garde(range(min = 1))]
+    pub tp_term: i32, // years
+
+    #[garde(range(min = 1))]
+    pub ep_term: i32, // years
+}
+
+// Scenarios projected by base_cf
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum ScenarioSpec {
+    // Every combination of interest rate, risk and premium term scenario
+    #[default]
+    All,
+    // The listed standard scenarios, in the listed order
+    Standard(Vec<Scenario>),
+    Custom(Vec<CustomScenario>),
+}
+
+// Basis of a run that is chosen by the caller rather than read from the policy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunConfig {
+    pub shock: AssumptionShock,
+    pub arithmetic: ArithmeticEnum, // Number type of the projection kernel
+}
+
+impl ScenarioSpec {
+    pub fn runs(
+        &self,
+        base: &Base,
+        rules: &ProductRules,
+        config: &RunConfig,
+    ) -> PolarsResult<Vec<ScenarioRun>> {
+        match self {
+            ScenarioSpec::All => iproduct!(
+                IntRateScenarioEnum::iter(),
+                RiskTypeEnum::iter(),
+                PremTermScenarioEnum::iter()
+            )
+            .map(|scenario| ScenarioRun::standard(scenario, base, rules, config))
+            .collect(),
+            ScenarioSpec::Standard(scenarios) => scenarios
+                .iter()
+                .map(|scenario| ScenarioRun::standard(*scenario, base, rules, config))
+                .collect(),
+            ScenarioSpec::Custom(scenarios) => scenarios
+                .iter()
+                .map(|scenario| ScenarioRun::custom(scenario, base, rules, config))
+                .collect(),
+        }
+    }
+}
+
+// Scenario resolved against the base: the labels, declared rates and terms a projection needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioRun {
+    pub int_rate_scenario: String,
+    pub risk: RiskTypeEnum,
+    pub term_scenario: String,
+    pub declared_int_rate: Vec<(i32, f64)>,
+    pub tp_term: i32,
+    pub ep_term: i32,
+    pub max_admin_chrg: Option<f64>, // Caps the projected admin charge
+}
+
+impl ScenarioRun {
+    pub fn standard(
+        scenario: Scenario,
+        base: &Base,
+        rules: &ProductRules,
+        config: &RunConfig,
+    ) -> PolarsResult<Self> {
+        let (int_rate, risk, prem_term) = scenario;
+        let term = match prem_term {
+            PremTermScenarioEnum::PolicyTerm => ("Policy", base.term(rules)?, base.term(rules)?),
+            PremTermScenarioEnum::OptedTerm => ("Opted", base.opted_tp_term, base.opted_ep_term),
+            _ => ("Must-pay", base.must_pay_period()?, base.must_pay_period()?),
+        };
+
+        Ok(Self {
+            int_rate_scenario: int_rate.as_ref().to_string(),
+            risk,
+            term_scenario: term.0.to_string(),
+            declared_int_rate: base.int_rate_points(int_rate, &config.shock)?,
+            tp_term: term.1,
+            ep_term: term.2,
+            max_admin_chrg: match int_rate {
+                IntRateScenarioEnum::Guaranteed => base.max_admin_chrg()?,
+                _ => None,
+            },
+        })
+    }
+
+    pub fn custom(
+        scenario: &CustomScenario,
+        base: &Base,
+        rules: &ProductRules,
+        config: &RunConfig,
+    ) -> PolarsResult<Self> {
+        scenario.validate().map_err(|e| {
+            let err_msg = format!("Invalid scenario '{}': {}", scenario.name, e);
+            PolarsError::ComputeError(err_msg.into())
+        })?;
+        let term = base.term(rules)?;
+        if scenario.ep_term > scenario.tp_term || scenario.tp_term > term {
+            let err_msg = format!(
+                "Scenario '{}': EP term {} must not exceed TP term {}, nor TP term the policy term {}.",
+                scenario.name, scenario.ep_term, scenario.tp_term, term
+            );
+            return Err(PolarsError::ComputeError(err_msg.into()));
+        }
+
+        // Custom rates are projected rates, so the interest shock applies
+        let declared_int_rate = scenario
+            .int_rate
+            .iter()
+            .map(|p| (p.from, p.rate + config.shock.int_rate_shift))
+            .collect();
+
+        Ok(Self {
+            int_rate_scenario: scenario.name.clone(),
+            risk: scenario.risk,
+            term_scenario: scenario.name.clone(),
+            declared_int_rate,
+            tp_term: scenario.tp_term,
+            ep_term: scenario.ep_term,
+            max_admin_chrg: None,
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::read_json_struct;
+    use crate::structs::policy::Policy;
+
+    fn base() -> Base {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        read_json_struct::<Policy>(json_path).unwrap().base
+    }
+
+    #[test]
+    fn test_struct_scenario_spec_01() {
+        let base = base();
+        let rules = base.rules().unwrap();
+        assert_eq!(
+            ScenarioSpec::All
+                .runs(&base, &rules, &RunConfig::default())
+                .unwrap()
+                .len(),
+            18
+        );
+
+        let spec: ScenarioSpec =
+            serde_json::from_str(r#"{"Standard": [["Low", "Standard", "OptedTerm"]]}"#).unwrap();
+        let runs = spec.runs(&base, &rules, &RunConfig::default()).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].int_rate_scenario, "Low");
+        assert_eq!(runs[0].term_scenario, "Opted");
+        assert_eq!(runs[0].tp_term, base.opted_tp_term);
+    }
+
+    #[test]
+    fn test_struct_scenario_spec_02() {
+        // EP paid for longer than TP
+        let json = r#"{"Custom": [{"name": "Mid 6%", "int_rate": [{"from": 1, "rate": 0.06}],
+            "risk": "Standard", "tp_term": 10, "ep_term": 15}]}"#;
+        let spec: ScenarioSpec = serde_json::from_str(json).unwrap();
+        let base = base();
+        let rules = base.rules().unwrap();
+        assert!(spec.runs(&base, &rules, &RunConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_struct_scenario_spec_03() {
+        // No product sets a guaranteed maximum admin charge, so no scenario is capped
+        let base = base();
+        let rules = base.rules().unwrap();
+        for run in ScenarioSpec::All
+            .runs(&base, &rules, &RunConfig::default())
+            .unwrap()
+        {
+            assert_eq!(run.max_admin_chrg, None, "{}", run.int_rate_scenario);
+        }
+    }
+
+    #[test]
+    fn test_struct_run_config_01() {
+        // The shock comes with the run, and custom rates are shifted like High and Low
+        let config: RunConfig =
+            serde_json::from_str(r#"{"shock": {"name": "Interest -1%", "int_rate_shift": -0.01}}"#)
+                .unwrap();
+        let json = r#"{"Custom": [{"name": "Mid 6%", "int_rate": [{"from": 1, "rate": 0.06}],
+            "risk": "Standard", "tp_term": 10, "ep_term": 10}]}"#;
+        let spec: ScenarioSpec = serde_json::from_str(json).unwrap();
+        let base = base();
+        let runs = spec.runs(&base, &base.rules().unwrap(), &config).unwrap();
+        approx::assert_abs_diff_eq!(runs[0].declared_int_rate[0].1, 0.05, epsilon = 1e-12);
+
+        // An empty config is the unshocked basis
+        let config: RunConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, RunConfig::default());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::cashflows::base_cf::base_cf;
+use crate::cashflows::scenario::{RunConfig, ScenarioSpec};
+use crate::database::shock::AssumptionShock;
+use crate::structs::policy::Policy;
+use polars::prelude::*;
+
+const KEY_COLS: [&str; 4] = [
+    "int_rate_scenario",
+    "risk_scenario",
+    "term_scenario",
+    "year",
+];
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn _summary(lf: LazyFrame) -> LazyFrame {
+    // Rows after a lapse keep the default deduction_flag of false, so the first one is the lapse year
+    let lapse_year = col("year").filter(col("deduction_flag").not()).min().over([
+        col("int_rate_scenario"),
+        col("risk_scenario"),
+        col("term_scenario"),
+    ]);
+
+    lf.select([
+        col("int_rate_scenario"),
+        col("risk_scenario"),
+        col("term_scenario"),
+        col("year"),
+        col("end_pav"),
+        col("srr_val"),
+        lapse_year.alias("lapse_year"),
+    ])
+}
+
+fn _compare(shocked: LazyFrame, unshocked: LazyFrame) -> LazyFrame {
+    let unshocked = unshocked.select([
+        cols(KEY_COLS),
+        col("end_pav").alias("base_end_pav"),
+        col("srr_val").alias("base_srr_val"),
+        col("lapse_year").alias("base_lapse_year"),
+    ]);
+    let keys: Vec<Expr> = KEY_COLS.iter().map(|c| col(*c)).collect();
+
+    shocked
+        .join(unshocked, keys.clone(), keys, JoinArgs::new(JoinType::Left))
+        .with_columns([
+            (col("end_pav") - col("base_end_pav")).alias("end_pav_change"),
+            (col("srr_val") - col("base_srr_val")).alias("srr_val_change"),
+        ])
+        .select([
+            cols(KEY_COLS),
+            col("end_pav"),
+            col("base_end_pav"),
+            col("end_pav_change"),
+            col("srr_val"),
+            col("base_srr_val"),
+            col("srr_val_change"),
+            col("lapse_year"),
+            col("base_lapse_year"),
+        ])
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Re-runs the base illustration of each policy under each assumption shock.
+///
+/// Every shocked row is compared with the unshocked run of the same scenario and year.
+/// The output reports the change in `end_pav` and `srr_val`, and the lapse year of both runs.
+/// A null lapse year means the scenario stays in force to maturity.
+pub fn sensitivity(policies: &[Policy], shocks: &[AssumptionShock]) -> PolarsResult<LazyFrame> {
+    let mut lfs = Vec::with_capacity(policies.len() * shocks.len());
+
+    for policy in policies {
+        let base = &policy.base;
+        let unshocked = _summary(base_cf(base, &ScenarioSpec::All, &RunConfig::default())?)
+            .collect()?
+            .lazy();
+
+        for shock in shocks {
+            let config = RunConfig {
+                shock: shock.clone(),
+                ..Default::default()
+            };
+            let shocked = _summary(base_cf(base, &ScenarioSpec::All, &config)?);
+            let lf = _compare(shocked, unshocked.clone()).with_columns([
+                lit(policy.id.as_str()).alias("policy_id"),
+                lit(shock.name.as_str()).alias("shock"),
+            ]);
+            lfs.push(lf.collect()?.lazy());
+        }
+    }
+
+    let lf = concat(lfs, Default::default())?;
+    Ok(lf.select([
+        col("policy_id"),
+        col("shock"),
+        all().exclude(["policy_id", "shock"]),
+    ]))
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::read_json_struct;
+
+    #[test]
+    fn test_fn_sensitivity_01() {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        // Withdrawals refused under the shock would lift the account value
+        policy.base.withdrawal_plan = None;
+        let shocks: Vec<AssumptionShock> =
+            read_json_struct("src/cashflows/test_data/sensitivity_shocks.json").unwrap();
+        let coi_shock = shocks.into_iter().find(|s| s.coi_rate_mult > 1.0).unwrap();
+
+        let df = sensitivity(&[policy], &[coi_shock])
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(
+            df.column("shock").unwrap().str().unwrap().get(0),
+            Some("COI x1.1")
+        );
+
+        // Higher COI can only lower the account value
+        let max_change = df
+            .column("end_pav_change")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .max()
+            .unwrap();
+        assert!(max_change <= 1e-6, "end_pav rose by {}", max_change);
+        let min_change = df
+            .column("end_pav_change")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .min()
+            .unwrap();
+        assert!(min_change < 0.0);
+    }
+}
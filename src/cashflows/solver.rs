@@ -0,0 +1,381 @@
+use crate::cashflows::base_cf::scenario_cf_select;
+use crate::cashflows::scenario::{RunConfig, Scenario};
+use crate::database::product::ProductRules;
+use crate::structs::policy::Policy;
+use polars::prelude::*;
+
+// SI and EP are searched on a 1,000 grid, the same rounding as modal_tp_tuple/modal_ep_tuple
+const AMOUNT_STEP: f64 = 1_000.0;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn _with_value(policy: &Policy, var: SolveVarEnum, x: i64) -> Policy {
+    let mut policy = policy.clone();
+    let base = &mut policy.base;
+    match var {
+        SolveVarEnum::Si => base.si = x as f64 * AMOUNT_STEP,
+        SolveVarEnum::Ep => base.ep = x as f64 * AMOUNT_STEP,
+        SolveVarEnum::OptedTpTerm => base.opted_tp_term = x as i32,
+        SolveVarEnum::OptedEpTerm => base.opted_ep_term = x as i32,
+    }
+    policy
+}
+
+fn _bounds(policy: &Policy, var: SolveVarEnum, rules: &ProductRules) -> PolarsResult<(i64, i64)> {
+    let base = &policy.base;
+    let limit = base.amount_limit()?;
+    let on_grid = |min: f64, max: f64| {
+        (
+            (min / AMOUNT_STEP).ceil() as i64,
+            (max / AMOUNT_STEP).floor() as i64,
+        )
+    };
+    let must_pay_period = base.must_pay_period()?;
+
+    // Amounts stay within the product limits, and terms within the rules checked by Policy validation
+    let bounds = match var {
+        SolveVarEnum::Si => on_grid(limit.min_si, limit.max_si),
+        SolveVarEnum::Ep => on_grid(limit.min_ep, limit.max_ep),
+        SolveVarEnum::OptedTpTerm => (
+            i32::max(must_pay_period, base.opted_ep_term) as i64,
+            base.term(rules)? as i64,
+        ),
+        SolveVarEnum::OptedEpTerm => (must_pay_period as i64, base.opted_tp_term as i64),
+    };
+    Ok(bounds)
+}
+
+fn _is_met(
+    policy: &Policy,
+    target: &SolveTargetEnum,
+    rules: &ProductRules,
+    config: &RunConfig,
+) -> PolarsResult<bool> {
+    let base = &policy.base;
+    match *target {
+        SolveTargetEnum::AnnualPrem(amount) => {
+            let annual_prem = base.modal_tp_tuple(rules)?.0 + base.modal_ep_tuple(rules)?.0;
+            Ok(annual_prem <= amount)
+        }
+        SolveTargetEnum::EndPav {
+            year,
+            scenario,
+            amount,
+        } => {
+            let df = scenario_cf_select(scenario, base, rules, config, &["year", "end_pav"])?
+                .filter(col("year").eq(lit(year)))
+                .select([col("end_pav")])
+                .collect()?;
+            // Years after a lapse or beyond the term carry no account value
+            let end_pav = df.column("end_pav")?.f64()?.get(0).unwrap_or(0.0);
+            Ok(end_pav >= amount)
+        }
+        SolveTargetEnum::NoLapse(scenario) => {
+            let df = scenario_cf_select(scenario, base, rules, config, &["deduction_flag"])?
+                .collect()?;
+            Ok(df.column("deduction_flag")?.bool()?.all())
+        }
+    }
+}
+
+// Validity is checked at every probed value: the rules tie SI, EP and terms to each other
+// and to the rate tables, so valid ends do not make every value in between valid
+fn _check_valid(policy: &Policy) -> PolarsResult<()> {
+    policy.validate_fields().map_err(|issues| {
+        let messages: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.is_error())
+            .map(|issue| issue.message.as_str())
+            .collect();
+        let err_msg = format!("Policy fails validation: {}", messages.join(" "));
+        PolarsError::ComputeError(err_msg.into())
+    })?;
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveVarEnum {
+    Si,
+    Ep,
+    OptedTpTerm,
+    OptedEpTerm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolveTargetEnum {
+    // Annual TP plus EP must not exceed the amount
+    AnnualPrem(f64),
+    // end_pav of the policy year under the scenario must reach the amount
+    EndPav {
+        year: i32,
+        scenario: Scenario,
+        amount: f64,
+    },
+    // Deductions must be covered every year until maturity under the scenario
+    NoLapse(Scenario),
+}
+
+#[derive(Debug, Clone)]
+pub enum SolveResult {
+    // The target flips within the range, at the value set in the policy
+    Solved(Policy),
+    // The whole range meets the target, so the lowest value is kept
+    MetAtLowerBound(Policy),
+    // The whole range meets the target and the highest value is kept, for budgets and SI
+    MetAtUpperBound(Policy),
+    // No value within the range meets the target
+    Unreachable,
+}
+
+/// Searches the base SI, EP or an opted term for the value that hits the target.
+///
+/// SI and EP are searched within the product's `ul_amount_limit`, terms within the validation rules.
+/// The policy must pass validation at every value the search probes, or the search stops with an error.
+/// The target is assumed to flip once over the range, which is found by bisection.
+/// A premium budget (AnnualPrem) returns the largest value within budget.
+/// Other targets return the smallest value that meets them.
+/// When the whole range meets the target, the result says so: the upper bound for budgets and SI,
+/// the lower bound otherwise. A target no value meets is `Unreachable`.
+pub fn solve(
+    policy: &Policy,
+    var: SolveVarEnum,
+    target: SolveTargetEnum,
+    config: &RunConfig,
+) -> PolarsResult<SolveResult> {
+    // The searched values never change the product, so its rules hold for every step
+    let rules = policy.base.rules()?;
+    let (lo, hi) = _bounds(policy, var, &rules)?;
+    if lo > hi {
+        let err_msg = format!("No room to solve {:?}: range is {} to {}.", var, lo, hi);
+        return Err(PolarsError::ComputeError(err_msg.into()));
+    }
+    let is_met = |x: i64| {
+        let policy = _with_value(policy, var, x);
+        _check_valid(&policy)?;
+        _is_met(&policy, &target, &rules, config)
+    };
+    let prefer_max = matches!(target, SolveTargetEnum::AnnualPrem(_)) || var == SolveVarEnum::Si;
+
+    let x = match (is_met(lo)?, is_met(hi)?) {
+        (true, true) if prefer_max => {
+            return Ok(SolveResult::MetAtUpperBound(_with_value(policy, var, hi)));
+        }
+        (true, true) => return Ok(SolveResult::MetAtLowerBound(_with_value(policy, var, lo))),
+        (false, false) => return Ok(SolveResult::Unreachable),
+        // Met at the bottom of the range: keep the largest value that still meets it
+        (true, false) => {
+            let (mut met, mut not_met) = (lo, hi);
+            while not_met - met > 1 {
+                let mid = met + (not_met - met) / 2;
+                if is_met(mid)? {
+                    met = mid
+                } else {
+                    not_met = mid
+                }
+            }
+            met
+        }
+        // Met at the top of the range: keep the smallest value that meets it
+        (false, true) => {
+            let (mut not_met, mut met) = (lo, hi);
+            while met - not_met > 1 {
+                let mid = not_met + (met - not_met) / 2;
+                if is_met(mid)? {
+                    met = mid
+                } else {
+                    not_met = mid
+                }
+            }
+            met
+        }
+    };
+
+    Ok(SolveResult::Solved(_with_value(policy, var, x)))
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{IntRateScenarioEnum, PremTermScenarioEnum, RiskTypeEnum};
+    use crate::helpers::read_json_struct;
+
+    fn policy() -> Policy {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        read_json_struct::<Policy>(json_path).unwrap()
+    }
+
+    #[test]
+    fn test_fn_solve_si_01() {
+        // What SI can 10M a year buy, keeping the EP of the illustration
+        let budget = 10_000_000.0;
+        let target = SolveTargetEnum::AnnualPrem(budget);
+        let result = solve(&policy(), SolveVarEnum::Si, target, &RunConfig::default()).unwrap();
+        let SolveResult::Solved(solved) = result else {
+            panic!("{:?}", result);
+        };
+
+        let base = &solved.base;
+        let rules = base.rules().unwrap();
+        let annual_prem = |b: &crate::structs::base::Base| {
+            b.modal_tp_tuple(&rules).unwrap().0 + b.modal_ep_tuple(&rules).unwrap().0
+        };
+        assert!(annual_prem(base) <= budget);
+
+        let mut next = base.clone();
+        next.si += AMOUNT_STEP;
+        assert!(annual_prem(&next) > budget);
+    }
+
+    #[test]
+    fn test_fn_solve_ep_01() {
+        // What EP reaches 1.5B at age 60 under the Low scenario
+        let policy = policy();
+        let rules = policy.base.rules().unwrap();
+        let year = 60 - policy.base.entry_age(&rules).unwrap() + 1;
+        let scenario = (
+            IntRateScenarioEnum::Low,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        );
+        let target = SolveTargetEnum::EndPav {
+            year,
+            scenario,
+            amount: 1_500_000_000.0,
+        };
+        let result = solve(&policy, SolveVarEnum::Ep, target, &RunConfig::default()).unwrap();
+        let SolveResult::Solved(solved) = result else {
+            panic!("{:?}", result);
+        };
+        assert!(_is_met(&solved, &target, &rules, &RunConfig::default()).unwrap());
+        assert_eq!(solved.base.ep % AMOUNT_STEP, 0.0);
+
+        let mut below = solved.clone();
+        below.base.ep -= AMOUNT_STEP;
+        assert!(!_is_met(&below, &target, &rules, &RunConfig::default()).unwrap());
+    }
+
+    #[test]
+    fn test_fn_solve_si_02() {
+        // Budget below the premium of the minimum SI
+        let target = SolveTargetEnum::AnnualPrem(1_000.0);
+        let result = solve(&policy(), SolveVarEnum::Si, target, &RunConfig::default()).unwrap();
+        assert!(matches!(result, SolveResult::Unreachable), "{:?}", result);
+    }
+
+    #[test]
+    fn test_fn_solve_si_03() {
+        // A budget that buys any SI stops at the product maximum
+        let target = SolveTargetEnum::AnnualPrem(1e15);
+        let result = solve(&policy(), SolveVarEnum::Si, target, &RunConfig::default()).unwrap();
+        let SolveResult::MetAtUpperBound(solved) = result else {
+            panic!("{:?}", result);
+        };
+        assert_eq!(solved.base.si, solved.base.amount_limit().unwrap().max_si);
+    }
+
+    #[test]
+    fn test_fn_solve_ep_02() {
+        // Any EP reaches a nil account value in year 1, so the lowest EP is kept
+        let scenario = (
+            IntRateScenarioEnum::High,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::PolicyTerm,
+        );
+        let target = SolveTargetEnum::EndPav {
+            year: 1,
+            scenario,
+            amount: 0.0,
+        };
+        let result = solve(&policy(), SolveVarEnum::Ep, target, &RunConfig::default()).unwrap();
+        let SolveResult::MetAtLowerBound(solved) = result else {
+            panic!("{:?}", result);
+        };
+        assert_eq!(solved.base.ep, solved.base.amount_limit().unwrap().min_ep);
+    }
+
+    fn opted(int_rate: IntRateScenarioEnum) -> Scenario {
+        (
+            int_rate,
+            RiskTypeEnum::Standard,
+            PremTermScenarioEnum::OptedTerm,
+        )
+    }
+
+    #[test]
+    fn test_fn_solve_opted_tp_term_01() {
+        // Shortest TP term that keeps the policy in force under the Guaranteed scenario,
+        // with the EP paid for 15 years only
+        let mut policy = policy();
+        policy.base.opted_ep_term = 15;
+        let rules = policy.base.rules().unwrap();
+        let target = SolveTargetEnum::NoLapse(opted(IntRateScenarioEnum::Guaranteed));
+        let var = SolveVarEnum::OptedTpTerm;
+        let result = solve(&policy, var, target, &RunConfig::default()).unwrap();
+        let SolveResult::Solved(solved) = result else {
+            panic!("{:?}", result);
+        };
+        assert!(_is_met(&solved, &target, &rules, &RunConfig::default()).unwrap());
+
+        let below = _with_value(&solved, var, solved.base.opted_tp_term as i64 - 1);
+        assert!(!_is_met(&below, &target, &rules, &RunConfig::default()).unwrap());
+    }
+
+    #[test]
+    fn test_fn_solve_opted_ep_term_01() {
+        // Shortest EP term that reaches 1B at age 60 under the Low scenario
+        let policy = policy();
+        let rules = policy.base.rules().unwrap();
+        let year = 60 - policy.base.entry_age(&rules).unwrap() + 1;
+        let target = SolveTargetEnum::EndPav {
+            year,
+            scenario: opted(IntRateScenarioEnum::Low),
+            amount: 1_000_000_000.0,
+        };
+        let var = SolveVarEnum::OptedEpTerm;
+        let result = solve(&policy, var, target, &RunConfig::default()).unwrap();
+        let SolveResult::Solved(solved) = result else {
+            panic!("{:?}", result);
+        };
+        assert!(_is_met(&solved, &target, &rules, &RunConfig::default()).unwrap());
+
+        let below = _with_value(&solved, var, solved.base.opted_ep_term as i64 - 1);
+        assert!(!_is_met(&below, &target, &rules, &RunConfig::default()).unwrap());
+    }
+
+    #[test]
+    fn test_fn_solve_no_lapse_01() {
+        // Smallest EP that keeps the policy in force under the Guaranteed scenario,
+        // with the TP paid for 10 years and the EP over the must-pay period
+        let mut policy = policy();
+        policy.base.opted_tp_term = 10;
+        policy.base.opted_ep_term = policy.base.must_pay_period().unwrap();
+        let rules = policy.base.rules().unwrap();
+        let target = SolveTargetEnum::NoLapse(opted(IntRateScenarioEnum::Guaranteed));
+        let result = solve(&policy, SolveVarEnum::Ep, target, &RunConfig::default()).unwrap();
+        let SolveResult::Solved(solved) = result else {
+            panic!("{:?}", result);
+        };
+        assert!(_is_met(&solved, &target, &rules, &RunConfig::default()).unwrap());
+
+        let mut below = solved.clone();
+        below.base.ep -= AMOUNT_STEP;
+        assert!(!_is_met(&below, &target, &rules, &RunConfig::default()).unwrap());
+    }
+
+    #[test]
+    fn test_fn_solve_validation_01() {
+        // An invalid policy is rejected before the search
+        let mut policy = policy();
+        policy.base.opted_ep_term = policy.base.opted_tp_term + 1;
+        let target = SolveTargetEnum::AnnualPrem(10_000_000.0);
+        let result = solve(&policy, SolveVarEnum::Si, target, &RunConfig::default());
+        assert!(result.is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::database::shock::AssumptionShock;
+use crate::database::table::{TableEnum, scan_table};
 use crate::enums::{GenderEnum, IntRateScenarioEnum, ULEnum};
 use polars::prelude::*;
 use strum_macros::{AsRefStr, EnumString};
@@ -5,6 +7,7 @@ use strum_macros::{AsRefStr, EnumString};
 pub mod admin_chrg;
 pub mod age_validation;
 pub mod alloc_chrg_rate;
+pub mod amount_limit;
 pub mod coi_rate;
 pub mod extra_prem_rate;
 pub mod int_rate;
@@ -12,4 +15,12 @@ pub mod juvenile_lien_rate;
 pub mod lb_rate;
 pub mod modal_factor;
 pub mod prem_rate;
+pub mod product;
+pub mod rider_death_ben;
+pub mod rider_prem_rate;
+pub mod rider_si_limit;
+pub mod rounding;
+pub mod sb_rate;
+pub mod shock;
 pub mod srr_chrg_rate;
+pub mod table;
@@ -1,13 +1,101 @@
 use super::*;
+use crate::enums::AdminChrgExtEnum;
+use std::str::FromStr;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// Amounts by calendar year through to_year, extended past the last year of the table by the rule
+fn _extend(
+    mut cal_years: Vec<i32>,
+    mut amounts: Vec<f64>,
+    rule: &AdminChrgRule,
+    to_year: i32,
+) -> PolarsResult<(Vec<i32>, Vec<f64>)> {
+    let (Some(&last_year), Some(&last_amount)) = (cal_years.last(), amounts.last()) else {
+        return Err(PolarsError::NoData("No admin charge".into()));
+    };
+
+    let index_rate = match rule.ext_rule {
+        _ if to_year <= last_year => return Ok((cal_years, amounts)),
+        AdminChrgExtEnum::Hold => 0.0,
+        AdminChrgExtEnum::Index => rule.index_rate,
+        AdminChrgExtEnum::Error => {
+            let err_msg = format!(
+                "Admin charge table ends in {}, before the projection ends in {}",
+                last_year, to_year
+            );
+            return Err(PolarsError::ComputeError(err_msg.into()));
+        }
+    };
+    for cal_year in (last_year + 1)..=to_year {
+        cal_years.push(cal_year);
+        amounts.push(last_amount * (1.0 + index_rate).powi(cal_year - last_year));
+    }
+    Ok((cal_years, amounts))
+}
 
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-pub fn get_admin_chrg_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
-    let path = "src/database/parquet/ul_admin_chrg.parquet";
-    let lf = LazyFrame::scan_parquet(path, Default::default())?
+// How the admin charge of a product runs on after its table, and its guaranteed maximum
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminChrgRule {
+    pub ext_rule: AdminChrgExtEnum,
+    pub index_rate: f64,             // Yearly growth under Index
+    pub max_admin_chrg: Option<f64>, // Cap in the Guaranteed scenario; -1 in the table for none
+}
+
+pub fn get_admin_chrg_rule(product: &ULEnum) -> PolarsResult<AdminChrgRule> {
+    let df = scan_table(&TableEnum::AdminChrgRule)?
+        .filter(col("product").eq(lit(product.as_ref())))
+        .select([col("ext_rule"), col("index_rate"), col("max_admin_chrg")])
+        .collect()?;
+
+    let ext_rule = df.column("ext_rule")?.str()?.get(0).ok_or_else(|| {
+        let err_msg = format!("No admin charge rule for {}", product.as_ref());
+        PolarsError::NoData(err_msg.into())
+    })?;
+    let ext_rule = AdminChrgExtEnum::from_str(ext_rule).map_err(|_| {
+        let err_msg = format!(
+            "Unknown admin charge rule {} for {}",
+            ext_rule,
+            product.as_ref()
+        );
+        PolarsError::ComputeError(err_msg.into())
+    })?;
+    let index_rate = df.column("index_rate")?.get(0)?.try_extract::<f64>()?;
+    let max_admin_chrg = df.column("max_admin_chrg")?.get(0)?.try_extract::<f64>()?;
+
+    Ok(AdminChrgRule {
+        ext_rule,
+        index_rate,
+        max_admin_chrg: (max_admin_chrg != -1.0).then_some(max_admin_chrg),
+    })
+}
+
+/// Monthly admin charge by calendar year through `to_year`, the last calendar year of the projection.
+pub fn get_admin_chrg_lf(
+    product: &ULEnum,
+    shock: &AssumptionShock,
+    to_year: i32,
+) -> PolarsResult<LazyFrame> {
+    let df = scan_table(&TableEnum::AdminChrg)?
         .filter(col("product").eq(lit(product.as_ref())))
-        .select([col("cal_year"), col("amount").alias("admin_chrg")]);
+        .sort(["cal_year"], Default::default())
+        .select([col("cal_year"), col("amount")])
+        .collect()?;
+    let cal_years: Vec<i32> = df.column("cal_year")?.i32()?.into_no_null_iter().collect();
+    let amounts: Vec<f64> = df.column("amount")?.f64()?.into_no_null_iter().collect();
+
+    let rule = get_admin_chrg_rule(product)?;
+    let (cal_years, amounts) = _extend(cal_years, amounts, &rule, to_year)?;
+
+    let lf = df![
+        "cal_year" => cal_years,
+        "admin_chrg" => amounts.iter().map(|a| a * shock.admin_chrg_mult).collect::<Vec<f64>>(),
+    ]?
+    .lazy();
     Ok(lf)
 }
 
@@ -17,19 +105,10 @@ pub fn get_admin_chrg_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    fn get_random_uvl_product() -> ULEnum {
-        // Not applicable for UVL03
-        let random_num = rand::Rng::random_range(&mut rand::rng(), 0..=2);
-        match random_num {
-            0 => ULEnum::UVL02,
-            1 => ULEnum::UVL03,
-            _ => ULEnum::ILP01,
-        }
-    }
 
     #[test]
     fn test_fn_get_admin_chrg_lf_01() {
-        let result = get_admin_chrg_lf(&ULEnum::UVL01)
+        let result = get_admin_chrg_lf(&ULEnum::UVL01, &AssumptionShock::default(), 2115)
             .unwrap()
             .collect()
             .unwrap();
@@ -46,7 +125,10 @@ mod tests {
     #[test]
     fn test_fn_get_admin_chrg_lf_02() {
         for product in [ULEnum::UVL02, ULEnum::UVL03, ULEnum::ILP01] {
-            let result = get_admin_chrg_lf(&product).unwrap().collect().unwrap();
+            let result = get_admin_chrg_lf(&product, &AssumptionShock::default(), 2123)
+                .unwrap()
+                .collect()
+                .unwrap();
 
             let expected = df![
                 "cal_year" => (2024..=2123).collect::<Vec<i32>>(),
@@ -56,7 +138,7 @@ mod tests {
 
             assert!(result.equals(&expected));
         }
-        let result = get_admin_chrg_lf(&ULEnum::UVL02)
+        let result = get_admin_chrg_lf(&ULEnum::UVL02, &AssumptionShock::default(), 2123)
             .unwrap()
             .collect()
             .unwrap();
@@ -69,4 +151,52 @@ mod tests {
 
         assert!(result.equals(&expected));
     }
+
+    #[test]
+    fn test_fn_get_admin_chrg_lf_03() {
+        // Every product holds its last amount after the table
+        for (product, last_amount) in [(ULEnum::UVL01, 25_000.0), (ULEnum::ILP01, 66_000.0)] {
+            let df = get_admin_chrg_lf(&product, &AssumptionShock::default(), 2125)
+                .unwrap()
+                .collect()
+                .unwrap();
+            let cal_year = df.column("cal_year").unwrap().i32().unwrap();
+            let amount = df.column("admin_chrg").unwrap().f64().unwrap();
+            assert_eq!(cal_year.last(), Some(2125));
+            assert_eq!(amount.last(), Some(last_amount));
+        }
+    }
+
+    #[test]
+    fn test_fn_extend_01() {
+        let rule = |ext_rule| AdminChrgRule {
+            ext_rule,
+            index_rate: 0.05,
+            max_admin_chrg: None,
+        };
+        let table = || (vec![2024, 2025], vec![10.0, 20.0]);
+
+        // Within the table nothing is added, whatever the rule
+        let (cal_years, _) =
+            _extend(table().0, table().1, &rule(AdminChrgExtEnum::Error), 2025).unwrap();
+        assert_eq!(cal_years, vec![2024, 2025]);
+
+        let (cal_years, amounts) =
+            _extend(table().0, table().1, &rule(AdminChrgExtEnum::Hold), 2027).unwrap();
+        assert_eq!(cal_years, vec![2024, 2025, 2026, 2027]);
+        assert_eq!(amounts, vec![10.0, 20.0, 20.0, 20.0]);
+
+        let (_, amounts) =
+            _extend(table().0, table().1, &rule(AdminChrgExtEnum::Index), 2026).unwrap();
+        approx::assert_abs_diff_eq!(amounts[2], 21.0, epsilon = 1e-12);
+
+        assert!(_extend(table().0, table().1, &rule(AdminChrgExtEnum::Error), 2026).is_err());
+    }
+
+    #[test]
+    fn test_fn_get_admin_chrg_rule_01() {
+        let rule = get_admin_chrg_rule(&ULEnum::UVL01).unwrap();
+        assert_eq!(rule.ext_rule, AdminChrgExtEnum::Hold);
+        assert_eq!(rule.max_admin_chrg, None);
+    }
 }
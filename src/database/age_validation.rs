@@ -7,6 +7,8 @@ use super::*;
 pub enum AgeValidationTypeEnum {
     #[strum(serialize = "min_entry_age")]
     Min,
+    #[strum(serialize = "min_entry_month_age")]
+    MinMonth,
     #[strum(serialize = "max_entry_age")]
     Max,
     #[strum(serialize = "maturity_age")]
@@ -19,8 +21,7 @@ where
     T: AsRef<str> + std::fmt::Debug,
 {
     let col_name = validate_type.as_ref();
-    let path = "src/database/parquet/age_validation.parquet";
-    let age = LazyFrame::scan_parquet(path, Default::default())?
+    let age = scan_table(&TableEnum::AgeValidation)?
         .filter(col("product").eq(lit(product.as_ref())))
         .select([col(col_name)])
         .collect()?
@@ -41,6 +42,14 @@ where
     get_age_validation(product, AgeValidationTypeEnum::Min)
 }
 
+// Minimum entry age in months for insureds younger than one year
+pub fn get_min_entry_month_age<T>(product: &T) -> PolarsResult<i32>
+where
+    T: AsRef<str> + std::fmt::Debug,
+{
+    get_age_validation(product, AgeValidationTypeEnum::MinMonth)
+}
+
 pub fn get_max_entry_age<T>(product: &T) -> PolarsResult<i32>
 where
     T: AsRef<str> + std::fmt::Debug,
@@ -85,6 +94,19 @@ mod tests {
         assert_eq!(result, 18_i32);
     }
 
+    #[test]
+    fn test_fn_get_min_entry_month_age_01() {
+        let product = get_random_uvl_product();
+        let result = get_min_entry_month_age(&product).unwrap();
+        assert_eq!(result, -1_i32);
+    }
+
+    #[test]
+    fn test_fn_get_min_entry_month_age_02() {
+        let result = get_min_entry_month_age(&RiderEnum::ADD01).unwrap();
+        assert_eq!(result, -1_i32);
+    }
+
     // Test fn get_max_entry_age
     #[test]
     fn test_fn_get_max_entry_age_01() {
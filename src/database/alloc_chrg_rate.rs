@@ -9,26 +9,42 @@ enum ChrgEnum {
     EP,
 }
 
-fn get_alloc_chrg_rate_lf(product: &ULEnum, chrg_type: ChrgEnum) -> PolarsResult<LazyFrame> {
+fn get_alloc_chrg_rate_lf(
+    product: &ULEnum,
+    chrg_type: ChrgEnum,
+    mult: f64,
+) -> PolarsResult<LazyFrame> {
     let chrg_type_str = chrg_type.as_ref().to_lowercase();
     let col_name = format!("{}_rate", chrg_type_str);
     let alias_name = format!("{}_alloc_chrg_rate", chrg_type_str);
-    let path = "src/database/parquet/ul_alloc_chrg_rate.parquet";
-    let lf = LazyFrame::scan_parquet(path, Default::default())?
+    let lf = scan_table(&TableEnum::AllocChrgRate)?
         .filter(col("product").eq(lit(product.as_ref())))
-        .select([col("year"), col(col_name).alias(alias_name)]);
+        .select([
+            col("year"),
+            // A scaled charge cannot take more than the whole premium
+            when((col(col_name.as_str()) * lit(mult)).gt(lit(1.0)))
+                .then(lit(1.0))
+                .otherwise(col(col_name.as_str()) * lit(mult))
+                .alias(alias_name),
+        ]);
     Ok(lf)
 }
 
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-pub fn get_tp_alloc_chrg_rate_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
-    get_alloc_chrg_rate_lf(product, ChrgEnum::TP)
+pub fn get_tp_alloc_chrg_rate_lf(
+    product: &ULEnum,
+    shock: &AssumptionShock,
+) -> PolarsResult<LazyFrame> {
+    get_alloc_chrg_rate_lf(product, ChrgEnum::TP, shock.tp_alloc_chrg_rate_mult)
 }
 
-pub fn get_ep_alloc_chrg_rate_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
-    get_alloc_chrg_rate_lf(product, ChrgEnum::EP)
+pub fn get_ep_alloc_chrg_rate_lf(
+    product: &ULEnum,
+    shock: &AssumptionShock,
+) -> PolarsResult<LazyFrame> {
+    get_alloc_chrg_rate_lf(product, ChrgEnum::EP, shock.ep_alloc_chrg_rate_mult)
 }
 
 // -----------------------------------------------------------------------------
@@ -40,7 +56,7 @@ mod tests {
 
     #[test]
     fn test_fn_get_tp_alloc_chrg_rate_lf_01() {
-        let result = get_tp_alloc_chrg_rate_lf(&ULEnum::UVL01)
+        let result = get_tp_alloc_chrg_rate_lf(&ULEnum::UVL01, &AssumptionShock::default())
             .unwrap()
             .filter(col("year").lt_eq(lit(3)).or(col("year").eq(lit(9))))
             .collect()
@@ -57,7 +73,7 @@ mod tests {
 
     #[test]
     fn test_fn_get_tp_alloc_chrg_rate_lf_02() {
-        let result = get_tp_alloc_chrg_rate_lf(&ULEnum::UVL01)
+        let result = get_tp_alloc_chrg_rate_lf(&ULEnum::UVL01, &AssumptionShock::default())
             .unwrap()
             .filter(col("year").gt_eq(lit(10)))
             .collect()
@@ -74,7 +90,7 @@ mod tests {
 
     #[test]
     fn test_fn_get_ep_alloc_chrg_rate_lf_01() {
-        let result = get_ep_alloc_chrg_rate_lf(&ULEnum::UVL01)
+        let result = get_ep_alloc_chrg_rate_lf(&ULEnum::UVL01, &AssumptionShock::default())
             .unwrap()
             .collect()
             .unwrap();
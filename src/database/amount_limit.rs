@@ -0,0 +1,48 @@
+use super::*;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Range of the base SI and EP a product accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmountLimit {
+    pub min_si: f64,
+    pub max_si: f64,
+    pub min_ep: f64,
+    pub max_ep: f64,
+}
+
+pub fn get_amount_limit(product: &ULEnum) -> PolarsResult<AmountLimit> {
+    let df = scan_table(&TableEnum::AmountLimit)?
+        .filter(col("product").eq(lit(product.as_ref())))
+        .select([col("min_si"), col("max_si"), col("min_ep"), col("max_ep")])
+        .collect()?;
+
+    if df.height() == 0 {
+        let err_msg = format!("No amount limits for {}", product.as_ref());
+        return Err(PolarsError::NoData(err_msg.into()));
+    }
+
+    let value = |name: &str| df.column(name)?.get(0)?.try_extract::<f64>();
+    Ok(AmountLimit {
+        min_si: value("min_si")?,
+        max_si: value("max_si")?,
+        min_ep: value("min_ep")?,
+        max_ep: value("max_ep")?,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_get_amount_limit_01() {
+        let result = get_amount_limit(&ULEnum::UVL01).unwrap();
+        assert_eq!(result.min_si, 100_000_000.0);
+        assert!(result.min_ep <= result.max_ep && result.min_si <= result.max_si);
+    }
+}
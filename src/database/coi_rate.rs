@@ -3,18 +3,24 @@ use super::*;
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-pub fn get_coi_rate_lf(product: &ULEnum, gender: &GenderEnum) -> PolarsResult<LazyFrame> {
+pub fn get_coi_rate_lf(
+    product: &ULEnum,
+    gender: &GenderEnum,
+    shock: &AssumptionShock,
+) -> PolarsResult<LazyFrame> {
     // Load the Parquet file into a LazyFrame
     let product_str = product.as_ref();
     let gender_num = *gender as i32;
-    let path = "src/database/parquet/ul_coi_rate.parquet";
-    let lf = LazyFrame::scan_parquet(path, Default::default())?
+    let lf = scan_table(&TableEnum::CoiRate)?
         .filter(
             col("product")
                 .eq(lit(product_str))
                 .and(col("gender").eq(lit(gender_num))),
         )
-        .select([col("age"), col("rate").alias("coi_rate")]);
+        .select([
+            col("age"),
+            (col("rate") * lit(shock.coi_rate_mult)).alias("coi_rate"),
+        ]);
     Ok(lf)
 }
 // -----------------------------------------------------------------------------
@@ -26,11 +32,15 @@ mod tests {
 
     #[test]
     fn test_fn_get_coi_rate_lf_01() {
-        let result = get_coi_rate_lf(&ULEnum::UVL01, &GenderEnum::Male)
-            .unwrap()
-            .filter(col("age").eq(lit(0)).or(col("age").eq(lit(26))))
-            .collect()
-            .unwrap();
+        let result = get_coi_rate_lf(
+            &ULEnum::UVL01,
+            &GenderEnum::Male,
+            &AssumptionShock::default(),
+        )
+        .unwrap()
+        .filter(col("age").eq(lit(0)).or(col("age").eq(lit(26))))
+        .collect()
+        .unwrap();
 
         let expected = df![
             "age" => &[0_i32, 26],
@@ -43,16 +53,20 @@ mod tests {
 
     #[test]
     fn test_fn_get_coi_rate_lf_02() {
-        let result = get_coi_rate_lf(&ULEnum::UVL01, &GenderEnum::Female)
-            .unwrap()
-            .filter(
-                col("age")
-                    .eq(lit(0))
-                    .or(col("age").eq(lit(42)))
-                    .or(col("age").eq(lit(99))),
-            )
-            .collect()
-            .unwrap();
+        let result = get_coi_rate_lf(
+            &ULEnum::UVL01,
+            &GenderEnum::Female,
+            &AssumptionShock::default(),
+        )
+        .unwrap()
+        .filter(
+            col("age")
+                .eq(lit(0))
+                .or(col("age").eq(lit(42)))
+                .or(col("age").eq(lit(99))),
+        )
+        .collect()
+        .unwrap();
 
         let expected = df![
             "age" => &[0_i32, 42, 99],
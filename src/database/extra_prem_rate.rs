@@ -9,9 +9,7 @@ pub fn get_extra_prem_rate(
     age: &i32,
     term: &i32,
 ) -> PolarsResult<f64> {
-    let path = "src/database/parquet/ul_extra_prem_rate.parquet";
-
-    let rate = LazyFrame::scan_parquet(path, Default::default())?
+    let rate = scan_table(&TableEnum::ExtraPremRate)?
         .filter(
             col("product")
                 .eq(lit(product.as_ref()))
@@ -3,33 +3,80 @@ use super::*;
 // -----------------------------------------------------------------------------
 // PRIVATE
 // -----------------------------------------------------------------------------
-fn get_int_rate(product: &ULEnum, scenario: IntRateScenarioEnum) -> PolarsResult<f64> {
-    let path = "src/database/parquet/ul_int_rate.parquet";
-    let rate = LazyFrame::scan_parquet(path, Default::default())?
+fn get_int_rate(
+    product: &ULEnum,
+    scenario: IntRateScenarioEnum,
+    shock: &AssumptionShock,
+) -> PolarsResult<f64> {
+    // Rate of the first policy year
+    get_int_rate_curve(product, scenario, shock)?
+        .first()
+        .map(|(_, rate)| *rate)
+        .ok_or_else(|| PolarsError::ComputeError("Interest rate not found".into()))
+}
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Crediting rate curve as (from policy year, rate), sorted by year.
+// Each rate applies from its year until the next point.
+pub fn get_int_rate_curve(
+    product: &ULEnum,
+    scenario: IntRateScenarioEnum,
+    shock: &AssumptionShock,
+) -> PolarsResult<Vec<(i32, f64)>> {
+    let df = scan_table(&TableEnum::IntRate)?
         .filter(
             col("product")
                 .eq(lit(product.as_ref()))
                 .and(col("scenario").eq(lit(scenario as i32))),
         )
-        .collect()?
-        .column("rate")?
-        .get(0)?
-        .try_extract::<f64>()?;
-    Ok(rate)
+        .sort(["year"], Default::default())
+        .collect()?;
+
+    // The guaranteed rate is contractual, so only the projected rates are shocked
+    let shift = match scenario {
+        IntRateScenarioEnum::Guaranteed => 0.0,
+        _ => shock.int_rate_shift,
+    };
+
+    let years = df.column("year")?.i32()?;
+    let rates = df.column("rate")?.f64()?;
+    let curve = years
+        .into_no_null_iter()
+        .zip(rates.into_no_null_iter())
+        .map(|(year, rate)| (year, rate + shift))
+        .collect();
+    Ok(curve)
 }
-// -----------------------------------------------------------------------------
-// PUBLIC
-// -----------------------------------------------------------------------------
-pub fn get_hir(product: &ULEnum) -> PolarsResult<f64> {
-    get_int_rate(product, IntRateScenarioEnum::High)
+
+pub fn get_hir(product: &ULEnum, shock: &AssumptionShock) -> PolarsResult<f64> {
+    get_int_rate(product, IntRateScenarioEnum::High, shock)
+}
+
+pub fn get_lir(product: &ULEnum, shock: &AssumptionShock) -> PolarsResult<f64> {
+    get_int_rate(product, IntRateScenarioEnum::Low, shock)
 }
 
-pub fn get_lir(product: &ULEnum) -> PolarsResult<f64> {
-    get_int_rate(product, IntRateScenarioEnum::Low)
+pub fn get_gir(product: &ULEnum, shock: &AssumptionShock) -> PolarsResult<f64> {
+    get_int_rate(product, IntRateScenarioEnum::Guaranteed, shock)
 }
 
-pub fn get_gir(product: &ULEnum) -> PolarsResult<f64> {
-    get_int_rate(product, IntRateScenarioEnum::Guaranteed)
+// Policy years in which the guaranteed rate floors the declared rate; None for the whole policy term
+pub fn get_guarantee_period(product: &ULEnum) -> PolarsResult<Option<i32>> {
+    let df = scan_table(&TableEnum::IntRateGuarantee)?
+        .filter(col("product").eq(lit(product.as_ref())))
+        .select([col("guarantee_period")])
+        .collect()?;
+
+    let guarantee_period = df
+        .column("guarantee_period")?
+        .i32()?
+        .get(0)
+        .ok_or_else(|| {
+            let err_msg = format!("No guarantee period for {}", product.as_ref());
+            PolarsError::NoData(err_msg.into())
+        })?;
+    Ok((guarantee_period != -1).then_some(guarantee_period))
 }
 // -----------------------------------------------------------------------------
 // UNIT TESTS
@@ -51,12 +98,47 @@ mod tests {
     fn test_fn_int_rate_01() {
         // UVL high interest rate
         let product = get_random_uvl_product();
+        let shock = AssumptionShock::default();
         let result = (
-            get_hir(&product).unwrap(),
-            get_lir(&product).unwrap(),
-            get_gir(&product).unwrap(),
+            get_hir(&product, &shock).unwrap(),
+            get_lir(&product, &shock).unwrap(),
+            get_gir(&product, &shock).unwrap(),
         );
         let expected = (0.07f64, 0.05f64, 0.02_f64);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_fn_int_rate_02() {
+        // Interest -1% moves the projected rates but not the guaranteed rate
+        let shock = AssumptionShock {
+            int_rate_shift: -0.01,
+            ..Default::default()
+        };
+        let result = (
+            get_hir(&ULEnum::UVL01, &shock).unwrap(),
+            get_lir(&ULEnum::UVL01, &shock).unwrap(),
+            get_gir(&ULEnum::UVL01, &shock).unwrap(),
+        );
+        approx::assert_abs_diff_eq!(result.0, 0.06, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(result.1, 0.04, epsilon = 1e-12);
+        assert_eq!(result.2, 0.02);
+    }
+
+    #[test]
+    fn test_fn_get_guarantee_period_01() {
+        // UVL products guarantee the rate for the whole term, ILP01 not at all
+        let result = get_guarantee_period(&get_random_uvl_product()).unwrap();
+        assert_eq!(result, None);
+        let result = get_guarantee_period(&ULEnum::ILP01).unwrap();
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn test_fn_get_int_rate_curve_01() {
+        // Flat curves start from the first policy year
+        let shock = AssumptionShock::default();
+        let result = get_int_rate_curve(&ULEnum::UVL01, IntRateScenarioEnum::Low, &shock).unwrap();
+        assert_eq!(result, vec![(1, 0.05)]);
+    }
 }
@@ -4,8 +4,7 @@ use super::*;
 // PUBLIC
 // -----------------------------------------------------------------------------
 pub fn get_juvenile_lien_rate_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
-    let path = "src/database/parquet/ul_juvenile_lien_rate.parquet";
-    let lf = LazyFrame::scan_parquet(path, Default::default())?
+    let lf = scan_table(&TableEnum::JuvenileLienRate)?
         .filter(col("product").eq(lit(product.as_ref())))
         .select([col("age"), col("rate").alias("juvenile_lien_rate")]);
     Ok(lf)
@@ -35,7 +34,7 @@ mod tests {
     #[test]
     fn test_fn_get_juvenile_lien_rate_lf_02() {
         for product in &[ULEnum::UVL02, ULEnum::UVL03, ULEnum::ILP01] {
-            let result = get_juvenile_lien_rate_lf(&product)
+            let result = get_juvenile_lien_rate_lf(product)
                 .unwrap()
                 .collect()
                 .unwrap();
@@ -4,8 +4,7 @@ use super::*;
 // PUBLIC
 // -----------------------------------------------------------------------------
 pub fn get_lb_rate_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
-    let path = "src/database/parquet/ul_lb_rate.parquet";
-    let lf = LazyFrame::scan_parquet(path, ScanArgsParquet::default())?
+    let lf = scan_table(&TableEnum::LbRate)?
         .filter(col("product").eq(lit(product.as_ref())))
         .select([col("year"), col("rate").alias("lb_rate")]);
     Ok(lf)
@@ -40,13 +39,7 @@ mod tests {
                 .get(year - 1)
                 .unwrap();
             let expected = match product {
-                ULEnum::UVL01 | ULEnum::ILP01 => {
-                    if year % 4 == 0 {
-                        0.06_f64 * ((year / 4) as f64)
-                    } else {
-                        0_f64
-                    }
-                }
+                ULEnum::UVL01 | ULEnum::ILP01 if year % 4 == 0 => 0.06_f64 * ((year / 4) as f64),
                 _ => 0_f64,
             };
             assert!(
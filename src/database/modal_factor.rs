@@ -6,8 +6,7 @@ pub fn get_modal_factor_tuple<T>(product: &T) -> PolarsResult<(f64, f64, f64, f6
 where
     T: AsRef<str> + std::fmt::Debug,
 {
-    let path = "src/database/parquet/modal_factor.parquet";
-    let df = LazyFrame::scan_parquet(path, Default::default())?
+    let df = scan_table(&TableEnum::ModalFactor)?
         .filter(col("product").eq(lit(product.as_ref())))
         .sort(["mode"], Default::default()) // Default ascending sort
         .select([col("mode"), col("rate")])
@@ -15,7 +14,7 @@ where
 
     // Check if the DataFrame has exactly 4 rows - representing the 4 modes
     if df.height() != 4 {
-        return Err(PolarsError::NoData("Expected 4 rows".into()).into());
+        return Err(PolarsError::NoData("Expected 4 rows".into()));
     }
 
     // Assume the DataFrame has 4 rows, mode values 0 to 3 in order
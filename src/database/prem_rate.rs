@@ -4,8 +4,7 @@ use super::*;
 // PUBLIC
 // -----------------------------------------------------------------------------
 pub fn get_prem_rate(product: &ULEnum, gender: &GenderEnum, age: &i32) -> PolarsResult<f64> {
-    let path = "src/database/parquet/ul_prem_rate.parquet";
-    let lf = LazyFrame::scan_parquet(path, Default::default())?;
+    let lf = scan_table(&TableEnum::PremRate)?;
     let rate = lf
         .filter(
             col("product")
@@ -42,7 +41,8 @@ mod tests {
 
     #[test]
     fn test_fn_get_prem_rate_03() {
+        // Setting!E3 of the workbook: age 0 is rated 10.6128 for both genders
         let result = get_prem_rate(&ULEnum::UVL01, &GenderEnum::Female, &0).unwrap();
-        assert_eq!(result, 10.56_f64);
+        assert_eq!(result, 10.6128_f64);
     }
 }
@@ -0,0 +1,98 @@
+use super::*;
+use crate::database::rounding::{RoundingRules, get_rounding_rules};
+use crate::enums::{AgeBasisEnum, CurrencyEnum};
+use std::str::FromStr;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// Text attribute of a product, parsed into its enum
+fn _get_attribute<T, E>(product: &T, col_name: &str) -> PolarsResult<E>
+where
+    T: AsRef<str> + std::fmt::Debug,
+    E: FromStr,
+{
+    let df = scan_table(&TableEnum::Product)?
+        .filter(col("product").eq(lit(product.as_ref())))
+        .select([col(col_name)])
+        .collect()?;
+
+    let value = df.column(col_name)?.str()?.get(0).ok_or_else(|| {
+        let err_msg = format!("No product {}", product.as_ref());
+        PolarsError::NoData(err_msg.into())
+    })?;
+
+    E::from_str(value).map_err(|_| {
+        let err_msg = format!("Unknown {} {} for {}", col_name, value, product.as_ref());
+        PolarsError::ComputeError(err_msg.into())
+    })
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+pub fn get_age_basis<T>(product: &T) -> PolarsResult<AgeBasisEnum>
+where
+    T: AsRef<str> + std::fmt::Debug,
+{
+    _get_attribute(product, "age_basis")
+}
+
+pub fn get_currency<T>(product: &T) -> PolarsResult<CurrencyEnum>
+where
+    T: AsRef<str> + std::fmt::Debug,
+{
+    _get_attribute(product, "currency")
+}
+
+// Rules of a product that a run reads at every step. Loaded once per run and passed down,
+// so the product and rounding tables are not scanned again for each premium or age.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductRules {
+    pub age_basis: AgeBasisEnum,
+    pub rounding: RoundingRules,
+}
+
+pub fn get_product_rules<T>(product: &T) -> PolarsResult<ProductRules>
+where
+    T: AsRef<str> + std::fmt::Debug,
+{
+    Ok(ProductRules {
+        age_basis: get_age_basis(product)?,
+        rounding: get_rounding_rules(product)?,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::RiderEnum;
+
+    #[test]
+    fn test_fn_get_age_basis_01() {
+        let result = get_age_basis(&ULEnum::UVL01).unwrap();
+        assert_eq!(result, AgeBasisEnum::LastBirthday);
+    }
+
+    #[test]
+    fn test_fn_get_age_basis_02() {
+        let result = get_age_basis(&RiderEnum::HOP02).unwrap();
+        assert_eq!(result, AgeBasisEnum::LastBirthday);
+    }
+
+    #[test]
+    fn test_fn_get_currency_01() {
+        let result = get_currency(&ULEnum::UVL01).unwrap();
+        assert_eq!(result, CurrencyEnum::VND);
+    }
+
+    #[test]
+    fn test_fn_get_product_rules_01() {
+        let result = get_product_rules(&ULEnum::UVL01).unwrap();
+        assert_eq!(result.age_basis, AgeBasisEnum::LastBirthday);
+        assert_eq!(result.rounding, get_rounding_rules(&ULEnum::UVL01).unwrap());
+    }
+}
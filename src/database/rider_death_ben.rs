@@ -0,0 +1,34 @@
+use super::*;
+use crate::enums::RiderEnum;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Share of the rider SI paid on the death of the rider insured
+pub fn get_rider_death_ben_pct(rider: &RiderEnum) -> PolarsResult<f64> {
+    let df = scan_table(&TableEnum::RiderDeathBen)?
+        .filter(col("product").eq(lit(rider.as_ref())))
+        .select([col("si_pct")])
+        .collect()?;
+
+    if df.height() == 0 {
+        let err_msg = format!("No death benefit for {}", rider.as_ref());
+        return Err(PolarsError::NoData(err_msg.into()));
+    }
+
+    df.column("si_pct")?.get(0)?.try_extract::<f64>()
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_get_rider_death_ben_pct_01() {
+        assert_eq!(get_rider_death_ben_pct(&RiderEnum::ADD01).unwrap(), 1.0);
+        assert_eq!(get_rider_death_ben_pct(&RiderEnum::CIR01).unwrap(), 0.0);
+    }
+}
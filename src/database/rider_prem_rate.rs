@@ -0,0 +1,128 @@
+use super::*;
+use crate::enums::RiderEnum;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Annual rates of the Tariff sheet of the UVL workbook, looked up for one policy year.
+// Rate is per 1000 SI, except SUP01 and HOP02 where it is the annual premium itself:
+// - ADD01: flat add_rate (B3), per 1 SI in the workbook
+// - PPD01: Annualized GPR by band of attained age (E:G), per 1 SI in the workbook
+// - CIR01, CIR02: by gender and attained age (J:L, O:Q)
+// - HOP02: premium of the plan (T:V)
+// - SUP01: premium by attained age and remaining term (CB:CS), for an SI of 10,000,000
+// - WOP01: by gender, entry age and rider term (AH:BY), per year of remaining term
+// - WOP02: by gender and attained age (Y:AA), before the discount factor of the remaining term
+// Term is the remaining term for SUP01, the rider term for WOP01 and 0 otherwise.
+// Option is the plan of the rider (hop2_option for HOP02) and 0 for riders without plans.
+pub fn get_rider_prem_rate(
+    rider: &RiderEnum,
+    gender: &GenderEnum,
+    age: &i32,
+    term: &i32,
+    option: &i32,
+) -> PolarsResult<f64> {
+    let df = scan_table(&TableEnum::RiderPremRate)?
+        .filter(
+            col("product")
+                .eq(lit(rider.as_ref()))
+                .and(col("gender").eq(lit(*gender as i32)))
+                .and(col("age").eq(lit(*age)))
+                .and(col("term").eq(lit(*term)))
+                .and(col("option").eq(lit(*option))),
+        )
+        .select([col("rate")])
+        .collect()?;
+
+    if df.height() == 0 {
+        let err_msg = format!(
+            "No premium rate for rider {} at age {}, term {}, option {}",
+            rider.as_ref(),
+            age,
+            term,
+            option
+        );
+        return Err(PolarsError::NoData(err_msg.into()));
+    }
+
+    let rate = df.column("rate")?.get(0)?.try_extract::<f64>()?;
+    Ok(rate)
+}
+
+// Plans of the rider in the rate table, in ascending order
+pub fn get_rider_options(rider: &RiderEnum) -> PolarsResult<Vec<i32>> {
+    let df = scan_table(&TableEnum::RiderPremRate)?
+        .filter(col("product").eq(lit(rider.as_ref())))
+        .select([col("option").unique().sort(Default::default())])
+        .collect()?;
+    let options = df
+        .column("option")?
+        .cast(&DataType::Int32)?
+        .i32()?
+        .into_no_null_iter()
+        .collect();
+    Ok(options)
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_get_rider_prem_rate_01() {
+        // Tariff!B3 of 1.4E-3 per 1 SI
+        let result =
+            get_rider_prem_rate(&RiderEnum::ADD01, &GenderEnum::Male, &30, &0, &0).unwrap();
+        assert_eq!(result, 1.4_f64);
+    }
+
+    #[test]
+    fn test_fn_get_rider_prem_rate_02() {
+        // HOP02 is rated by plan: Tariff!V6
+        let result =
+            get_rider_prem_rate(&RiderEnum::HOP02, &GenderEnum::Female, &31, &0, &4).unwrap();
+        assert_eq!(result, 360000.0_f64);
+    }
+
+    #[test]
+    fn test_fn_get_rider_prem_rate_03() {
+        // Past the last attained age of the rider
+        let result = get_rider_prem_rate(&RiderEnum::ADD01, &GenderEnum::Male, &61, &0, &0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_fn_get_rider_prem_rate_04() {
+        // PPD01 band of 61 to 65 (Tariff!G4), CIR01 by gender (Tariff!K33:L33)
+        let result =
+            get_rider_prem_rate(&RiderEnum::PPD01, &GenderEnum::Female, &63, &0, &0).unwrap();
+        assert_eq!(result, 1.42_f64);
+        let male = get_rider_prem_rate(&RiderEnum::CIR01, &GenderEnum::Male, &31, &0, &0).unwrap();
+        let female =
+            get_rider_prem_rate(&RiderEnum::CIR01, &GenderEnum::Female, &31, &0, &0).unwrap();
+        assert_eq!((male, female), (1.67_f64, 2.27_f64));
+    }
+
+    #[test]
+    fn test_fn_get_rider_prem_rate_05() {
+        // SUP01 by remaining term (Tariff!CC16), WOP01 by rider term (Tariff!BL18)
+        let result =
+            get_rider_prem_rate(&RiderEnum::SUP01, &GenderEnum::Male, &31, &17, &0).unwrap();
+        assert_eq!(result, 354237.0_f64);
+        let result =
+            get_rider_prem_rate(&RiderEnum::WOP01, &GenderEnum::Female, &31, &34, &0).unwrap();
+        assert_eq!(result, 2.12_f64);
+        // No WOP01 rate past the maturity age of 65
+        let result = get_rider_prem_rate(&RiderEnum::WOP01, &GenderEnum::Female, &31, &35, &0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_fn_get_rider_options_01() {
+        let result = get_rider_options(&RiderEnum::HOP02).unwrap();
+        assert_eq!(result, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,42 @@
+use super::*;
+use crate::enums::RiderEnum;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Minimum and maximum SI of a rider; None for riders rated by plan, which carry no SI
+pub fn get_rider_si_limit(rider: &RiderEnum) -> PolarsResult<Option<(f64, f64)>> {
+    let df = scan_table(&TableEnum::RiderSiLimit)?
+        .filter(col("product").eq(lit(rider.as_ref())))
+        .select([col("min_si"), col("max_si")])
+        .collect()?;
+
+    if df.height() == 0 {
+        return Ok(None);
+    }
+
+    let min_si = df.column("min_si")?.get(0)?.try_extract::<f64>()?;
+    let max_si = df.column("max_si")?.get(0)?.try_extract::<f64>()?;
+    Ok(Some((min_si, max_si)))
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_get_rider_si_limit_01() {
+        let result = get_rider_si_limit(&RiderEnum::CIR01).unwrap();
+        // No limits are set yet
+        assert_eq!(result, Some((-1.0, -1.0)));
+    }
+
+    #[test]
+    fn test_fn_get_rider_si_limit_02() {
+        let result = get_rider_si_limit(&RiderEnum::HOP02).unwrap();
+        assert_eq!(result, None);
+    }
+}
@@ -0,0 +1,137 @@
+use super::*;
+use crate::enums::{RoundedQuantityEnum, RoundingEnum};
+use crate::num::Num;
+use std::str::FromStr;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundingRule {
+    pub quantity: RoundedQuantityEnum,
+    pub unit: f64, // e.g. 1000 for VND thousands, 0.01 for USD cents
+    pub direction: RoundingEnum,
+}
+
+impl RoundingRule {
+    pub fn round<N: Num>(&self, value: N) -> PolarsResult<N> {
+        value.round_to(self.unit, self.direction)
+    }
+}
+
+// Rounding of a product; amounts without a rule are not rounded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundingRules(pub Vec<RoundingRule>);
+
+impl RoundingRules {
+    pub fn rule(&self, quantity: RoundedQuantityEnum) -> Option<&RoundingRule> {
+        self.0.iter().find(|rule| rule.quantity == quantity)
+    }
+
+    pub fn round<N: Num>(&self, quantity: RoundedQuantityEnum, value: N) -> PolarsResult<N> {
+        match self.rule(quantity) {
+            Some(rule) => rule.round(value),
+            None => Ok(value),
+        }
+    }
+}
+
+pub fn get_rounding_rules<T>(product: &T) -> PolarsResult<RoundingRules>
+where
+    T: AsRef<str> + std::fmt::Debug,
+{
+    let df = scan_table(&TableEnum::Rounding)?
+        .filter(col("product").eq(lit(product.as_ref())))
+        .select([col("quantity"), col("unit"), col("direction")])
+        .collect()?;
+    let quantities = df.column("quantity")?.str()?;
+    let units = df.column("unit")?.f64()?;
+    let directions = df.column("direction")?.str()?;
+
+    let parse_err = |value: Option<&str>| {
+        let err_msg = format!("Invalid rounding rule {:?} for {}", value, product.as_ref());
+        PolarsError::ComputeError(err_msg.into())
+    };
+    let rules = (0..df.height())
+        .map(|i| {
+            let quantity = quantities.get(i);
+            let direction = directions.get(i);
+            Ok(RoundingRule {
+                quantity: quantity
+                    .and_then(|q| RoundedQuantityEnum::from_str(q).ok())
+                    .ok_or_else(|| parse_err(quantity))?,
+                unit: units
+                    .get(i)
+                    .filter(|u| *u > 0.0)
+                    .ok_or_else(|| parse_err(quantity))?,
+                direction: direction
+                    .and_then(|d| RoundingEnum::from_str(d).ok())
+                    .ok_or_else(|| parse_err(direction))?,
+            })
+        })
+        .collect::<PolarsResult<Vec<RoundingRule>>>()?;
+    Ok(RoundingRules(rules))
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(unit: f64, direction: RoundingEnum) -> RoundingRule {
+        RoundingRule {
+            quantity: RoundedQuantityEnum::ModalPrem,
+            unit,
+            direction,
+        }
+    }
+
+    #[test]
+    fn test_struct_rounding_rule_01() {
+        // VND thousands
+        assert_eq!(
+            rule(1000.0, RoundingEnum::Up).round(1_072_001.0).unwrap(),
+            1_073_000.0
+        );
+        assert_eq!(
+            rule(1000.0, RoundingEnum::Down).round(1_072_999.0).unwrap(),
+            1_072_000.0
+        );
+        // Ties go to the even unit
+        assert_eq!(
+            rule(1000.0, RoundingEnum::HalfEven).round(2_500.0).unwrap(),
+            2_000.0
+        );
+        assert_eq!(
+            rule(1000.0, RoundingEnum::HalfEven).round(3_500.0).unwrap(),
+            4_000.0
+        );
+        assert_eq!(
+            rule(1000.0, RoundingEnum::HalfEven).round(3_501.0).unwrap(),
+            4_000.0
+        );
+    }
+
+    #[test]
+    fn test_struct_rounding_rule_02() {
+        // USD cents
+        let cents = |v: f64, direction| rule(0.01, direction).round(v).unwrap();
+        approx::assert_abs_diff_eq!(cents(12.341, RoundingEnum::Up), 12.35, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(cents(12.349, RoundingEnum::Down), 12.34, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(cents(0.125, RoundingEnum::HalfEven), 0.12, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_fn_get_rounding_rules_01() {
+        // UVL01 rounds its premiums and loads up to 1,000 and nothing else
+        let rules = get_rounding_rules(&ULEnum::UVL01).unwrap();
+        assert_eq!(rules.0.len(), 2);
+        assert_eq!(rules.round(RoundedQuantityEnum::Load, 1.0).unwrap(), 1000.0);
+        assert_eq!(
+            rules.round(RoundedQuantityEnum::AccountValue, 1.5).unwrap(),
+            1.5
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Special bonus rule of a policy year and SI band.
+// The bonus is paid only if no TAV was withdrawn in the review window ending that year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SbRule {
+    pub year: i32,
+    pub min_si: f64, // Lower bound of the SI band
+    pub rate: f64,   // Multiple of the annual TP
+    pub review_period: i32,
+}
+
+pub fn get_sb_rate_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
+    let lf = scan_table(&TableEnum::SbRate)?
+        .filter(col("product").eq(lit(product.as_ref())))
+        .select([
+            col("year"),
+            col("min_si"),
+            col("rate").alias("sb_rate"),
+            col("review_period").alias("sb_review_period"),
+        ])
+        .sort(["year", "min_si"], Default::default());
+    Ok(lf)
+}
+
+pub fn get_sb_rules(product: &ULEnum) -> PolarsResult<Vec<SbRule>> {
+    let df = get_sb_rate_lf(product)?.collect()?;
+    let years = df.column("year")?.i32()?;
+    let min_sis = df.column("min_si")?.f64()?;
+    let rates = df.column("sb_rate")?.f64()?;
+    let review_periods = df.column("sb_review_period")?.i32()?;
+
+    let rules = (0..df.height())
+        .map(|i| SbRule {
+            year: years.get(i).unwrap_or(0),
+            min_si: min_sis.get(i).unwrap_or(0.0),
+            rate: rates.get(i).unwrap_or(0.0),
+            review_period: review_periods.get(i).unwrap_or(0),
+        })
+        .collect();
+    Ok(rules)
+}
+
+// Rule of the highest SI band the SI reaches in the policy year, if any
+pub fn find_sb_rule(rules: &[SbRule], year: i32, si: f64) -> Option<&SbRule> {
+    rules
+        .iter()
+        .filter(|rule| rule.year == year && rule.min_si <= si)
+        .max_by(|a, b| a.min_si.total_cmp(&b.min_si))
+}
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_random_no_sb_product() -> ULEnum {
+        // Not applicable for UVL02 and UVL03
+        let random_num = rand::Rng::random_range(&mut rand::rng(), 0..=1);
+        match random_num {
+            0 => ULEnum::UVL01,
+            _ => ULEnum::ILP01,
+        }
+    }
+
+    fn get_random_sb_product() -> ULEnum {
+        let random_num = rand::Rng::random_range(&mut rand::rng(), 0..=1);
+        match random_num {
+            0 => ULEnum::UVL02,
+            _ => ULEnum::UVL03,
+        }
+    }
+
+    #[test]
+    fn test_fn_get_sb_rules_01() {
+        let rules = get_sb_rules(&get_random_no_sb_product()).unwrap();
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn test_fn_get_sb_rules_02() {
+        let rules = get_sb_rules(&get_random_sb_product()).unwrap();
+        for year in 1..=25 {
+            for si in [100_000_000.0, 500_000_000.0, 999_999_999.0, 1_000_000_000.0] {
+                let rate = find_sb_rule(&rules, year, si).map_or(0.0, |rule| rule.rate);
+                let expected = match (year, si >= 1_000_000_000.0) {
+                    (11, true) => 0.4,
+                    (21, true) => 1.2,
+                    (11, false) => 0.2,
+                    (21, false) => 0.6,
+                    _ => 0.0,
+                };
+                assert!(
+                    (rate - expected).abs() < 1e-8,
+                    "year: {}, si: {}, got: {}, expected: {}",
+                    year,
+                    si,
+                    rate,
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fn_get_sb_rules_03() {
+        // Withdrawals are reviewed over the 10 years up to the bonus year
+        let rules = get_sb_rules(&ULEnum::UVL02).unwrap();
+        assert!(rules.iter().all(|rule| rule.review_period == 10));
+    }
+
+    #[test]
+    fn test_fn_get_sb_rules_04() {
+        // Bonus years as paid before the table: policy years 11 and 21
+        let rules = get_sb_rules(&get_random_sb_product()).unwrap();
+        let mut years: Vec<i32> = rules.iter().map(|rule| rule.year).collect();
+        years.dedup();
+        assert_eq!(years, vec![11, 21]);
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Shocks applied to the assumptions as the rate tables are loaded, so the parquet files stay untouched.
+// The default is the unshocked basis; a config only lists the assumptions it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssumptionShock {
+    pub name: String,
+    pub coi_rate_mult: f64,           // e.g. 1.1 for COI x1.1
+    pub int_rate_shift: f64,          // e.g. -0.01 for interest -1%; High and Low only
+    pub admin_chrg_mult: f64,         // e.g. 1.2 for admin charge +20%
+    pub tp_alloc_chrg_rate_mult: f64, // Capped at a 100% charge
+    pub ep_alloc_chrg_rate_mult: f64, // Capped at a 100% charge
+}
+
+impl Default for AssumptionShock {
+    fn default() -> Self {
+        Self {
+            name: "Base".to_string(),
+            coi_rate_mult: 1.0,
+            int_rate_shift: 0.0,
+            admin_chrg_mult: 1.0,
+            tp_alloc_chrg_rate_mult: 1.0,
+            ep_alloc_chrg_rate_mult: 1.0,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_struct_assumption_shock_01() {
+        // Unlisted assumptions keep the base basis
+        let json = r#"{"name": "COI +10%", "coi_rate_mult": 1.1}"#;
+        let shock: AssumptionShock = serde_json::from_str(json).unwrap();
+        assert_eq!(shock.coi_rate_mult, 1.1);
+        assert_eq!(shock.int_rate_shift, 0.0);
+        assert_eq!(shock.admin_chrg_mult, 1.0);
+    }
+}
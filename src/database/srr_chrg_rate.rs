@@ -4,8 +4,7 @@ use super::*;
 // PUBLIC
 // -----------------------------------------------------------------------------
 pub fn get_srr_chrg_rate_lf(product: &ULEnum) -> PolarsResult<LazyFrame> {
-    let path = "src/database/parquet/ul_srr_chrg_rate.parquet";
-    let lf = LazyFrame::scan_parquet(path, ScanArgsParquet::default())?
+    let lf = scan_table(&TableEnum::SrrChrgRate)?
         .filter(col("product").eq(lit(product.as_ref())))
         .select([col("year"), col("rate").alias("srr_chrg_rate")]);
     Ok(lf)
@@ -0,0 +1,171 @@
+use super::*;
+use crate::helpers::df_to_json_records;
+use sha2::{Digest, Sha256};
+use strum_macros::EnumIter;
+
+// Embedded tables are compiled into the binary, so lookups do not depend on the working directory.
+// This is always the case on wasm, where there is no file system to scan.
+#[cfg(any(feature = "embedded-tables", target_arch = "wasm32"))]
+macro_rules! embed_table {
+    ($name:literal) => {
+        include_bytes!(concat!("parquet/", $name, ".parquet")).as_slice()
+    };
+}
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+#[cfg(not(any(feature = "embedded-tables", target_arch = "wasm32")))]
+fn _scan(table: &TableEnum) -> PolarsResult<LazyFrame> {
+    LazyFrame::scan_parquet(table.path(), Default::default())
+}
+
+#[cfg(any(feature = "embedded-tables", target_arch = "wasm32"))]
+fn _scan(table: &TableEnum) -> PolarsResult<LazyFrame> {
+    #[cfg(target_arch = "wasm32")]
+    use polars_io::{SerReader, parquet::read::ParquetReader};
+
+    let df = ParquetReader::new(std::io::Cursor::new(table.bytes())).finish()?;
+    Ok(df.lazy())
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Rate tables read by the engine; the serialized name is the parquet file stem.
+#[derive(Copy, Clone, AsRefStr, Debug, PartialEq, Eq, Hash, EnumString, EnumIter)]
+pub enum TableEnum {
+    #[strum(serialize = "age_validation")]
+    AgeValidation,
+    #[strum(serialize = "modal_factor")]
+    ModalFactor,
+    #[strum(serialize = "product")]
+    Product,
+    #[strum(serialize = "rider_death_ben")]
+    RiderDeathBen,
+    #[strum(serialize = "rider_prem_rate")]
+    RiderPremRate,
+    #[strum(serialize = "rider_si_limit")]
+    RiderSiLimit,
+    #[strum(serialize = "rounding")]
+    Rounding,
+    #[strum(serialize = "ul_admin_chrg")]
+    AdminChrg,
+    #[strum(serialize = "ul_admin_chrg_rule")]
+    AdminChrgRule,
+    #[strum(serialize = "ul_alloc_chrg_rate")]
+    AllocChrgRate,
+    #[strum(serialize = "ul_amount_limit")]
+    AmountLimit,
+    #[strum(serialize = "ul_coi_rate")]
+    CoiRate,
+    #[strum(serialize = "ul_extra_prem_rate")]
+    ExtraPremRate,
+    #[strum(serialize = "ul_int_rate")]
+    IntRate,
+    #[strum(serialize = "ul_int_rate_guarantee")]
+    IntRateGuarantee,
+    #[strum(serialize = "ul_juvenile_lien_rate")]
+    JuvenileLienRate,
+    #[strum(serialize = "ul_lb_rate")]
+    LbRate,
+    #[strum(serialize = "ul_prem_rate")]
+    PremRate,
+    #[strum(serialize = "ul_sb_rate")]
+    SbRate,
+    #[strum(serialize = "ul_srr_chrg_rate")]
+    SrrChrgRate,
+}
+
+impl TableEnum {
+    pub fn path(&self) -> String {
+        format!("src/database/parquet/{}.parquet", self.as_ref())
+    }
+
+    #[cfg(any(feature = "embedded-tables", target_arch = "wasm32"))]
+    pub fn bytes(&self) -> &'static [u8] {
+        match self {
+            TableEnum::AgeValidation => embed_table!("age_validation"),
+            TableEnum::ModalFactor => embed_table!("modal_factor"),
+            TableEnum::Product => embed_table!("product"),
+            TableEnum::RiderDeathBen => embed_table!("rider_death_ben"),
+            TableEnum::RiderPremRate => embed_table!("rider_prem_rate"),
+            TableEnum::RiderSiLimit => embed_table!("rider_si_limit"),
+            TableEnum::Rounding => embed_table!("rounding"),
+            TableEnum::AdminChrg => embed_table!("ul_admin_chrg"),
+            TableEnum::AdminChrgRule => embed_table!("ul_admin_chrg_rule"),
+            TableEnum::AllocChrgRate => embed_table!("ul_alloc_chrg_rate"),
+            TableEnum::AmountLimit => embed_table!("ul_amount_limit"),
+            TableEnum::CoiRate => embed_table!("ul_coi_rate"),
+            TableEnum::ExtraPremRate => embed_table!("ul_extra_prem_rate"),
+            TableEnum::IntRate => embed_table!("ul_int_rate"),
+            TableEnum::IntRateGuarantee => embed_table!("ul_int_rate_guarantee"),
+            TableEnum::JuvenileLienRate => embed_table!("ul_juvenile_lien_rate"),
+            TableEnum::LbRate => embed_table!("ul_lb_rate"),
+            TableEnum::PremRate => embed_table!("ul_prem_rate"),
+            TableEnum::SbRate => embed_table!("ul_sb_rate"),
+            TableEnum::SrrChrgRate => embed_table!("ul_srr_chrg_rate"),
+        }
+    }
+}
+
+pub fn scan_table(table: &TableEnum) -> PolarsResult<LazyFrame> {
+    _scan(table)
+}
+
+/// SHA-256 of the rows of the listed products, as hex; None when the table has no rows for them.
+/// Rows of other products do not enter the hash, so editing them leaves it unchanged.
+pub fn table_hash(table: &TableEnum, products: &[&str]) -> PolarsResult<Option<String>> {
+    let of_products = products
+        .iter()
+        .map(|product| col("product").eq(lit(*product)))
+        .reduce(|a, b| a.or(b))
+        .unwrap_or(lit(false));
+    let df = scan_table(table)?.filter(of_products).collect()?;
+    if df.height() == 0 {
+        return Ok(None);
+    }
+    let records = df_to_json_records(&df)?;
+    let hash = Sha256::digest(records.to_string().as_bytes());
+    Ok(Some(format!("{:x}", hash)))
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_fn_scan_table_01() {
+        for table in TableEnum::iter() {
+            let df = scan_table(&table).unwrap().collect().unwrap();
+            assert!(df.height() > 0, "Table {} is empty", table.as_ref());
+            assert!(
+                df.column("product").is_ok(),
+                "Table {} has no product",
+                table.as_ref()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fn_table_hash_01() {
+        let uvl01 = table_hash(&TableEnum::CoiRate, &["UVL01"])
+            .unwrap()
+            .unwrap();
+        let both = table_hash(&TableEnum::CoiRate, &["UVL01", "UVL02"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(uvl01.len(), 64);
+        assert_ne!(uvl01, both);
+        assert_eq!(
+            Some(uvl01),
+            table_hash(&TableEnum::CoiRate, &["UVL01"]).unwrap()
+        );
+        // Riders have no COI rates
+        assert_eq!(table_hash(&TableEnum::CoiRate, &["ADD01"]).unwrap(), None);
+    }
+}
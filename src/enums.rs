@@ -1,9 +1,20 @@
 use garde::Validate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use strum_macros::{AsRefStr, EnumIter, EnumString};
+use strum_macros::{AsRefStr, EnumIter, EnumString, IntoStaticStr};
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum GenderEnum {
@@ -14,7 +25,17 @@ pub enum GenderEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum PayModeEnum {
@@ -25,7 +46,17 @@ pub enum PayModeEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum ChannelEnum {
@@ -35,7 +66,17 @@ pub enum ChannelEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum StatusEnum {
@@ -46,7 +87,17 @@ pub enum StatusEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum DeathTPDBenefitEnum {
@@ -55,7 +106,17 @@ pub enum DeathTPDBenefitEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum RelationshipToOwnerEnum {
@@ -64,7 +125,17 @@ pub enum RelationshipToOwnerEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum FundEnum {
@@ -82,7 +153,17 @@ pub enum FundEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum IntRateScenarioEnum {
@@ -92,7 +173,17 @@ pub enum IntRateScenarioEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum PremTermScenarioEnum {
@@ -102,7 +193,17 @@ pub enum PremTermScenarioEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum RiskTypeEnum {
@@ -111,7 +212,165 @@ pub enum RiskTypeEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum YearBasisEnum {
+    PolicyYear,
+    CalendarYear,
+}
+
+// Admin charge after the last calendar year of the admin charge table
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum AdminChrgExtEnum {
+    Hold,  // Last amount of the table
+    Index, // Last amount grown at the index rate
+    Error, // Projection beyond the table is not allowed
+}
+
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum CurrencyEnum {
+    VND,
+    USD,
+}
+
+// Number type of the projection kernel
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    Default,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum ArithmeticEnum {
+    #[default]
+    Float, // f64, as the rate tables are stored
+    Decimal, // Exact decimal arithmetic, with inputs read from their shortest f64 text
+}
+
+// Direction of a rounding rule
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum RoundingEnum {
+    Up,
+    Down,
+    HalfEven,
+}
+
+// Amounts a product rounds, each at its own step of the calculation
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum RoundedQuantityEnum {
+    ModalPrem,    // Modal TP, EP and rider premiums
+    Load,         // EM and PM load premiums
+    Coi,          // Yearly COI
+    Deduction,    // Yearly deduction from the account value
+    Interest,     // Interest credited to each account
+    Bonus,        // Loyalty and special bonus
+    AccountValue, // EAV and TAV at the end of the year
+}
+
+// Age of the insured used for rating and the projection
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum AgeBasisEnum {
+    LastBirthday,
+    NearestBirthday,
+    NextBirthday,
+}
+
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum ULEnum {
@@ -124,7 +383,17 @@ pub enum ULEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum BaseEnum {
@@ -137,7 +406,17 @@ pub enum BaseEnum {
 }
 
 #[derive(
-    Copy, AsRefStr, Debug, PartialEq, EnumString, Clone, Serialize, Deserialize, Validate, EnumIter,
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
 )]
 #[garde(allow_unvalidated)]
 pub enum RiderEnum {
@@ -150,3 +429,142 @@ pub enum RiderEnum {
     WOP01,
     WOP02,
 }
+
+// Outcome of a single year's withdrawal, written to the withdrawal_log column
+#[derive(
+    Copy,
+    AsRefStr,
+    IntoStaticStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum WithdrawalLogEnum {
+    #[strum(serialize = "No withdrawal.")]
+    NoWithdrawal,
+    #[strum(serialize = "Withdrawal amount exceeds policy account value.")]
+    ExceedsPav,
+    #[strum(serialize = "After withdrawal, end PAV is below acceptable limit.")]
+    BelowMinPav,
+    #[strum(serialize = "After withdrawal, end SI is below acceptable limit.")]
+    BelowMinSi,
+    #[strum(serialize = "Successfully withdraw.")]
+    Success,
+}
+
+// Constraint that caps the sustainable withdrawal
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum WithdrawalConstraintEnum {
+    PolicyAccountValue,
+    MinPavAfterWithdrawal,
+    MinSi,
+    BonusForfeiture,
+    Lapse,
+}
+
+// Severity of a validation issue; warnings do not block the illustration
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum SeverityEnum {
+    Error,
+    Warning,
+}
+
+// Stable code of a validation issue, for front-ends to translate
+#[derive(
+    Copy,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    EnumString,
+    Clone,
+    Serialize,
+    Deserialize,
+    Validate,
+    EnumIter,
+    JsonSchema,
+)]
+#[garde(allow_unvalidated)]
+pub enum ValidationCodeEnum {
+    // Field rules
+    BelowMinimum,
+    AboveMaximum,
+    LengthBelowMinimum,
+    LengthAboveMaximum,
+    NotMultipleOfStep,
+    FundAllocEmpty,
+    FundAllocTpSum,
+    FundAllocEpSum,
+    WithdrawalFromAfterTo,
+    WithdrawalOverlap,
+    IntRateCurveEmpty,
+    IntRateCurveOrder,
+    InvalidValue,
+    // Age rules
+    EntryAgeBelowMinimum,
+    EntryMonthAgeBelowMinimum,
+    EntryAgeAboveMaximum,
+    NoCoverBeforeMaturity,
+    ManualReview,
+    // Base rules
+    OptedTpTermAboveTerm,
+    OptedEpTermAboveTpTerm,
+    OptedEpTermBelowMustPayPeriod,
+    EmLoadTermAboveTerm,
+    PmLoadTermAboveTerm,
+    WithdrawalBeforeStartYear,
+    FundAllocDefault,
+    EpOutOfRange,
+    // In-force rules
+    ValuationDateNotAnniversary,
+    ValuationDateBeyondTerm,
+    WithdrawalHistoryNotBeforeValuation,
+    // Rider rules
+    SiRequired,
+    SiOutOfRange,
+    Hop2OptionRequired,
+    Hop2OptionInvalid,
+    Hop2OptionNotApplicable,
+    RcdMismatch,
+    PaymodeMismatch,
+    DuplicateRider,
+    RiderCoverCappedByBase,
+    // Extract rules
+    MissingValue,
+    MissingBase,
+    DuplicateBase,
+    // A rate table or calculation needed by a rule failed
+    LookupFailed,
+}
@@ -0,0 +1,307 @@
+use crate::cashflows::base_cf::base_cf;
+use crate::cashflows::scenario::{RunConfig, ScenarioSpec};
+use crate::structs::policy::Policy;
+use polars::prelude::*;
+use std::ffi::{CStr, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+const SI_ENGINE_VERSION: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes()) {
+        Ok(v) => v,
+        Err(_) => panic!("Engine version is not a valid C string"),
+    };
+
+type FfiResult<T> = Result<T, (SiStatus, String)>;
+
+fn _parse_format(format: u32) -> FfiResult<SiOutputFormat> {
+    match format {
+        0 => Ok(SiOutputFormat::Csv),
+        1 => Ok(SiOutputFormat::Json),
+        _ => Err((
+            SiStatus::InvalidFormat,
+            format!("Unsupported output format {}.", format),
+        )),
+    }
+}
+
+fn _write_df(df: &mut DataFrame, format: SiOutputFormat) -> FfiResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let result = match format {
+        SiOutputFormat::Csv => CsvWriter::new(&mut bytes).finish(df),
+        SiOutputFormat::Json => JsonWriter::new(&mut bytes)
+            .with_json_format(JsonFormat::Json)
+            .finish(df),
+    };
+    result.map_err(|e| (SiStatus::SerializeError, e.to_string()))?;
+    Ok(bytes)
+}
+
+fn _illustrate(policy_json: &str, config: &RunConfig, format: u32) -> FfiResult<Vec<u8>> {
+    let format = _parse_format(format)?;
+
+    let policy: Policy =
+        serde_json::from_str(policy_json).map_err(|e| (SiStatus::ParseError, e.to_string()))?;
+
+    policy.validate_fields().map_err(|errors| {
+        let msg = serde_json::to_string(&errors).unwrap_or_default();
+        (SiStatus::ValidationError, msg)
+    })?;
+
+    let mut df = base_cf(&policy.base, &ScenarioSpec::All, config)
+        .and_then(|lf| lf.collect())
+        .map_err(|e| (SiStatus::CalculationError, e.to_string()))?;
+
+    _write_df(&mut df, format)
+}
+
+fn _parse_config(config_json: &str) -> FfiResult<RunConfig> {
+    serde_json::from_str(config_json).map_err(|e| (SiStatus::ParseError, e.to_string()))
+}
+
+fn _read_c_str<'a>(ptr: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if ptr.is_null() {
+        return Err((SiStatus::NullArgument, format!("{} pointer is null.", name)));
+    }
+    // Caller guarantees a NUL-terminated string that outlives this call
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|e| (SiStatus::InvalidUtf8, e.to_string()))
+}
+
+/// # Safety
+///
+/// `out` must point to writable memory for one `SiBuffer`.
+unsafe fn _write_result(
+    result: std::thread::Result<FfiResult<Vec<u8>>>,
+    out: *mut SiBuffer,
+) -> SiStatus {
+    let result = result.unwrap_or_else(|_| Err((SiStatus::Panic, "Engine panicked.".into())));
+    let (status, buffer) = match result {
+        Ok(bytes) => (SiStatus::Ok, SiBuffer::from_vec(bytes)),
+        Err((status, msg)) => (status, SiBuffer::from_vec(msg.into_bytes())),
+    };
+
+    unsafe { out.write(buffer) };
+    status
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Bumped whenever a signature, struct layout or enum value below changes.
+pub const SI_ABI_VERSION: u32 = 1;
+
+/// Status returned by every fallible ABI call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiStatus {
+    Ok = 0,
+    NullArgument = 1,
+    InvalidUtf8 = 2,
+    InvalidFormat = 3,
+    ParseError = 4,
+    ValidationError = 5,
+    CalculationError = 6,
+    SerializeError = 7,
+    Panic = 99,
+}
+
+/// Output format flag accepted by `si_illustrate`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiOutputFormat {
+    Csv = 0,
+    Json = 1,
+}
+
+/// Byte buffer owned by the library. `data` is NUL-terminated, `len` excludes the NUL.
+/// Release with `si_buffer_free`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SiBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl SiBuffer {
+    fn from_vec(mut bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        bytes.push(0);
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        SiBuffer { data, len }
+    }
+}
+
+/// ABI version of this library. Callers should refuse to run on a mismatch.
+#[unsafe(no_mangle)]
+pub extern "C" fn si_abi_version() -> u32 {
+    SI_ABI_VERSION
+}
+
+/// Engine (crate) version as a static NUL-terminated string. Do not free.
+#[unsafe(no_mangle)]
+pub extern "C" fn si_engine_version() -> *const c_char {
+    SI_ENGINE_VERSION.as_ptr()
+}
+
+/// Validates the policy JSON and projects all base scenarios.
+///
+/// On `SI_STATUS_OK`, `out` holds the illustration in the requested format.
+/// On any other status, `out` holds a UTF-8 error message.
+/// For `SI_STATUS_VALIDATION_ERROR` the message is a JSON array of issues `{"code", "pointer", "severity", "message", "params"}`,
+/// where `pointer` is the JSON pointer of the offending field, e.g. `/base/withdrawal_plan/1/from`.
+/// In both cases the buffer must be released with `si_buffer_free`.
+///
+/// # Safety
+///
+/// `policy_json` must be null or a valid NUL-terminated string.
+/// `out` must be null or point to writable memory for one `SiBuffer`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn si_illustrate(
+    policy_json: *const c_char,
+    format: u32,
+    out: *mut SiBuffer,
+) -> SiStatus {
+    if out.is_null() {
+        return SiStatus::NullArgument;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let json = _read_c_str(policy_json, "Policy JSON")?;
+        _illustrate(json, &RunConfig::default(), format)
+    }));
+    unsafe { _write_result(result, out) }
+}
+
+/// Same as `si_illustrate`, with the run basis read from `config_json`,
+/// e.g. `{"arithmetic": "Decimal", "shock": {"coi_rate_mult": 1.1}}`. Omitted keys keep their defaults.
+/// A malformed config is reported as `SI_STATUS_PARSE_ERROR`.
+///
+/// # Safety
+///
+/// `policy_json` and `config_json` must be null or valid NUL-terminated strings.
+/// `out` must be null or point to writable memory for one `SiBuffer`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn si_illustrate_with_config(
+    policy_json: *const c_char,
+    config_json: *const c_char,
+    format: u32,
+    out: *mut SiBuffer,
+) -> SiStatus {
+    if out.is_null() {
+        return SiStatus::NullArgument;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let json = _read_c_str(policy_json, "Policy JSON")?;
+        let config = _parse_config(_read_c_str(config_json, "Config JSON")?)?;
+        _illustrate(json, &config, format)
+    }));
+    unsafe { _write_result(result, out) }
+}
+
+/// Releases a buffer returned by this library. Freeing an empty buffer is a no-op.
+///
+/// # Safety
+///
+/// `buffer` must come from this library and must not be freed twice.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn si_buffer_free(buffer: SiBuffer) {
+    if buffer.data.is_null() {
+        return;
+    }
+    let slice = std::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len + 1);
+    drop(unsafe { Box::from_raw(slice) });
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn call(json: &CString, format: u32) -> (SiStatus, String) {
+        let mut out = SiBuffer {
+            data: std::ptr::null_mut(),
+            len: 0,
+        };
+        let status = unsafe { si_illustrate(json.as_ptr(), format, &mut out) };
+        _read_out(status, out)
+    }
+
+    fn call_with_config(json: &CString, config: &CString, format: u32) -> (SiStatus, String) {
+        let mut out = SiBuffer {
+            data: std::ptr::null_mut(),
+            len: 0,
+        };
+        let status =
+            unsafe { si_illustrate_with_config(json.as_ptr(), config.as_ptr(), format, &mut out) };
+        _read_out(status, out)
+    }
+
+    fn _read_out(status: SiStatus, out: SiBuffer) -> (SiStatus, String) {
+        let bytes = unsafe { std::slice::from_raw_parts(out.data, out.len) };
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        unsafe { si_buffer_free(out) };
+        (status, text)
+    }
+
+    #[test]
+    fn test_fn_si_illustrate_01() {
+        let json = std::fs::read_to_string("src/cashflows/test_data/uvl01_policy.json").unwrap();
+        let json = CString::new(json).unwrap();
+        let (status, text) = call(&json, SiOutputFormat::Csv as u32);
+        assert_eq!(status, SiStatus::Ok, "{}", text);
+        assert!(text.starts_with("year,age,cal_year"));
+    }
+
+    #[test]
+    fn test_fn_si_illustrate_02() {
+        // Malformed JSON is reported as a parse error with a message
+        let json = CString::new("{\"id\": ").unwrap();
+        let (status, text) = call(&json, SiOutputFormat::Json as u32);
+        assert_eq!(status, SiStatus::ParseError);
+        assert!(!text.is_empty());
+    }
+
+    #[test]
+    fn test_fn_si_illustrate_03() {
+        let json = CString::new("{}").unwrap();
+        let (status, _) = call(&json, 7);
+        assert_eq!(status, SiStatus::InvalidFormat);
+    }
+
+    #[test]
+    fn test_fn_si_illustrate_04() {
+        let status = unsafe { si_illustrate(std::ptr::null(), 0, std::ptr::null_mut()) };
+        assert_eq!(status, SiStatus::NullArgument);
+    }
+
+    #[test]
+    fn test_fn_si_illustrate_with_config_01() {
+        // The arithmetic is chosen by the caller, the output keeps the same columns
+        let json = std::fs::read_to_string("src/cashflows/test_data/uvl01_policy.json").unwrap();
+        let json = CString::new(json).unwrap();
+        let config = CString::new(r#"{"arithmetic": "Decimal"}"#).unwrap();
+        let (status, text) = call_with_config(&json, &config, SiOutputFormat::Csv as u32);
+        assert_eq!(status, SiStatus::Ok, "{}", text);
+        assert!(text.starts_with("year,age,cal_year"));
+
+        let (_, float) = call(&json, SiOutputFormat::Csv as u32);
+        assert_ne!(text, float);
+    }
+
+    #[test]
+    fn test_fn_si_illustrate_with_config_02() {
+        let json = CString::new("{}").unwrap();
+        let config = CString::new(r#"{"arithmetic": "Rational"}"#).unwrap();
+        let (status, text) = call_with_config(&json, &config, SiOutputFormat::Csv as u32);
+        assert_eq!(status, SiStatus::ParseError);
+        assert!(text.contains("Rational"), "{}", text);
+    }
+}
@@ -17,6 +17,40 @@ where
     Ok(data)
 }
 
+/// Converts a DataFrame into a JSON array of row objects keyed by column name.
+/// Written by hand because the polars JSON writer is not available on wasm.
+pub fn df_to_json_records(df: &DataFrame) -> PolarsResult<serde_json::Value> {
+    let names = df.get_column_names();
+    let mut records = Vec::with_capacity(df.height());
+
+    for i in 0..df.height() {
+        let mut record = serde_json::Map::with_capacity(names.len());
+        for (name, column) in names.iter().zip(df.get_columns()) {
+            let value = match column.get(i)? {
+                AnyValue::Null => serde_json::Value::Null,
+                AnyValue::Boolean(v) => v.into(),
+                AnyValue::Int32(v) => v.into(),
+                AnyValue::Int64(v) => v.into(),
+                AnyValue::UInt32(v) => v.into(),
+                AnyValue::Float64(v) => v.into(),
+                AnyValue::String(v) => v.into(),
+                AnyValue::StringOwned(v) => v.as_str().into(),
+                other => other.to_string().into(),
+            };
+            record.insert(name.to_string(), value);
+        }
+        records.push(serde_json::Value::Object(record));
+    }
+    Ok(serde_json::Value::Array(records))
+}
+
+/// Writes a DataFrame as CSV text, the format of the exported results in the test data.
+pub fn df_to_csv(df: &DataFrame) -> PolarsResult<String> {
+    let mut buffer = Vec::new();
+    CsvWriter::new(&mut buffer).finish(&mut df.clone())?;
+    String::from_utf8(buffer).map_err(|e| PolarsError::ComputeError(e.to_string().into()))
+}
+
 // Compares two objects that can be converted to a DataFrame (DataFrame or LazyFrame).
 pub fn compare_frames<F>(frame1: &F, frame2: &F) -> PolarsResult<bool>
 where
@@ -81,7 +115,7 @@ mod tests {
     #[test]
     fn test_fn_read_policy_json_01() {
         // Path to a valid policy.json file
-        let path = "src/models/test_data/uvl01_policy.json";
+        let path = "src/structs/test_data/uvl01_policy.json";
         let result = read_json_struct::<Policy>(path);
         assert!(result.is_ok(), "Expected Ok(Policy), got {:?}", result);
     }
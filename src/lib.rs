@@ -1,5 +1,10 @@
 pub mod cashflows;
 pub mod database;
 pub mod enums;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
 pub mod helpers;
+pub mod num;
 pub mod structs;
+#[cfg(any(target_arch = "wasm32", test))]
+pub mod wasm;
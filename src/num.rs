@@ -0,0 +1,255 @@
+use crate::enums::RoundingEnum;
+use polars::prelude::{PolarsError, PolarsResult};
+use rust_decimal::prelude::*;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Sub};
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn _conversion_err(value: impl std::fmt::Display, to: impl std::fmt::Display) -> PolarsError {
+    let err_msg = format!("{} cannot be converted to {}.", value, to);
+    PolarsError::ComputeError(err_msg.into())
+}
+
+fn _division_err(value: impl std::fmt::Display, by: impl std::fmt::Display) -> PolarsError {
+    let err_msg = format!("{} cannot be divided by {}.", value, by);
+    PolarsError::ComputeError(err_msg.into())
+}
+
+fn _powi(value: Decimal, exp: u32) -> Decimal {
+    (0..exp).fold(Decimal::ONE, |acc, _| acc * value)
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Number type of the projection kernel: `f64`, or `Decimal` for exact decimal results.
+/// Inputs and outputs stay `f64`, so both kernels read and write the same columns.
+/// Conversions fail on values the number type cannot hold, e.g. NaN for `Decimal`.
+pub trait Num:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Sum
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_input(value: f64) -> PolarsResult<Self>;
+    fn to_output(self) -> PolarsResult<f64>;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn pow(self, exp: i32) -> PolarsResult<Self>;
+    // Division that fails on a zero divisor, where f64 would give inf and Decimal would panic
+    fn try_div(self, other: Self) -> PolarsResult<Self>;
+    // Twelfth root, for monthly rates
+    fn root_12(self) -> PolarsResult<Self>;
+    // Rounds to a multiple of unit
+    fn round_to(self, unit: f64, direction: RoundingEnum) -> PolarsResult<Self>;
+
+    fn from_flag(flag: bool) -> Self {
+        if flag { Self::ONE } else { Self::ZERO }
+    }
+}
+
+impl Num for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_input(value: f64) -> PolarsResult<Self> {
+        Ok(value)
+    }
+
+    fn to_output(self) -> PolarsResult<f64> {
+        Ok(self)
+    }
+
+    fn max(self, other: Self) -> Self {
+        f64::max(self, other)
+    }
+
+    fn min(self, other: Self) -> Self {
+        f64::min(self, other)
+    }
+
+    // Powers as polars computes them, so the f64 kernel keeps the results of the lazy expressions
+    fn pow(self, exp: i32) -> PolarsResult<Self> {
+        Ok(self.powf(exp as f64))
+    }
+
+    fn try_div(self, other: Self) -> PolarsResult<Self> {
+        if other == 0.0 {
+            return Err(_division_err(self, other));
+        }
+        Ok(self / other)
+    }
+
+    fn root_12(self) -> PolarsResult<Self> {
+        Ok(self.powf(1_f64 / 12_f64))
+    }
+
+    fn round_to(self, unit: f64, direction: RoundingEnum) -> PolarsResult<Self> {
+        let units = self / unit;
+        let units = match direction {
+            RoundingEnum::Up => units.ceil(),
+            RoundingEnum::Down => units.floor(),
+            RoundingEnum::HalfEven => units.round_ties_even(),
+        };
+        Ok(units * unit)
+    }
+}
+
+impl Num for Decimal {
+    const ZERO: Self = Decimal::ZERO;
+    const ONE: Self = Decimal::ONE;
+
+    // Read from the shortest text that round-trips the f64, so every digit it shows is kept:
+    // a rate stored as 0.00188 reads as 0.00188, and large amounts keep all their digits
+    fn from_input(value: f64) -> PolarsResult<Self> {
+        if !value.is_finite() {
+            return Err(_conversion_err(value, "a decimal"));
+        }
+        Decimal::from_str(&value.to_string())
+            .map(|d| d.normalize())
+            .map_err(|_| _conversion_err(value, "a decimal"))
+    }
+
+    // The nearest f64, parsed from the text as well: to_f64 can miss it by a few units
+    fn to_output(self) -> PolarsResult<f64> {
+        f64::from_str(&self.to_string()).map_err(|_| _conversion_err(self, "an f64"))
+    }
+
+    fn max(self, other: Self) -> Self {
+        Ord::max(self, other)
+    }
+
+    fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+
+    fn pow(self, exp: i32) -> PolarsResult<Self> {
+        let power = _powi(self, exp.unsigned_abs());
+        if exp >= 0 {
+            return Ok(power);
+        }
+        Decimal::ONE
+            .checked_div(power)
+            .ok_or_else(|| _conversion_err(self, format!("the power {}", exp)))
+    }
+
+    fn try_div(self, other: Self) -> PolarsResult<Self> {
+        Decimal::checked_div(self, other).ok_or_else(|| _division_err(self, other))
+    }
+
+    // Newton's method from the f64 root, to the precision of a decimal
+    fn root_12(self) -> PolarsResult<Self> {
+        if self <= Decimal::ZERO {
+            return Err(_conversion_err(self, "a twelfth root"));
+        }
+        let twelve = Decimal::from(12);
+        let mut root = Decimal::from_input(self.to_output()?.root_12()?)?;
+        for _ in 0..10 {
+            let next = root - (root.pow(12)? - self) / (twelve * root.pow(11)?);
+            if next == root {
+                break;
+            }
+            root = next;
+        }
+        Ok(root)
+    }
+
+    fn round_to(self, unit: f64, direction: RoundingEnum) -> PolarsResult<Self> {
+        let unit = Decimal::from_input(unit)?;
+        let units = self / unit;
+        let units = match direction {
+            RoundingEnum::Up => units.ceil(),
+            RoundingEnum::Down => units.floor(),
+            RoundingEnum::HalfEven => {
+                units.round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+            }
+        };
+        Ok(units * unit)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_decimal_from_input_01() {
+        // Every digit the f64 shows is kept, however large the amount
+        assert_eq!(Decimal::from_input(0.00188).unwrap(), dec("0.00188"));
+        assert_eq!(
+            Decimal::from_input(123_456_789_012_345.67).unwrap(),
+            dec("123456789012345.67")
+        );
+        assert_eq!(
+            Decimal::from_input(0.07).unwrap().to_output().unwrap(),
+            0.07
+        );
+    }
+
+    #[test]
+    fn test_fn_decimal_to_output_01() {
+        // Trailing zeros left by a product do not move the nearest f64
+        let product = dec("0.00187999999999999") * dec("101000000");
+        assert_eq!(product.to_output().unwrap(), 189_879.999_999_998_98);
+    }
+
+    #[test]
+    fn test_fn_decimal_from_input_02() {
+        // Values a decimal cannot hold are errors, not zero
+        assert!(Decimal::from_input(f64::NAN).is_err());
+        assert!(Decimal::from_input(f64::INFINITY).is_err());
+        assert!(Decimal::from_input(1e30).is_err());
+    }
+
+    #[test]
+    fn test_fn_decimal_root_12_01() {
+        let root = dec("1.08").root_12().unwrap();
+        assert!((_powi(root, 12) - dec("1.08")).abs() < dec("0.0000000000000000000000001"));
+        approx::assert_abs_diff_eq!(
+            root.to_output().unwrap(),
+            1.08_f64.root_12().unwrap(),
+            epsilon = 1e-15
+        );
+    }
+
+    #[test]
+    fn test_fn_decimal_pow_01() {
+        assert_eq!(dec("1.1").pow(2).unwrap(), dec("1.21"));
+        assert_eq!(dec("1.25").pow(-1).unwrap(), dec("0.8"));
+        assert!(Decimal::ZERO.pow(-1).is_err());
+    }
+
+    #[test]
+    fn test_fn_try_div_01() {
+        assert_eq!(dec("1").try_div(dec("8")).unwrap(), dec("0.125"));
+        assert_eq!(2.0_f64.try_div(8.0).unwrap(), 0.25);
+        // A zero divisor is an error in both number types
+        assert!(dec("1").try_div(Decimal::ZERO).is_err());
+        assert!(1.0_f64.try_div(0.0).is_err());
+    }
+
+    #[test]
+    fn test_fn_decimal_round_to_01() {
+        let round = |v: &str, direction| dec(v).round_to(0.01, direction).unwrap();
+        assert_eq!(round("12.341", RoundingEnum::Up), dec("12.35"));
+        assert_eq!(round("12.349", RoundingEnum::Down), dec("12.34"));
+        // Ties go to the even cent
+        assert_eq!(round("0.125", RoundingEnum::HalfEven), dec("0.12"));
+        assert_eq!(round("0.135", RoundingEnum::HalfEven), dec("0.14"));
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+}
@@ -2,13 +2,18 @@ use crate::enums::*;
 use chrono::{Datelike, NaiveDate};
 use garde::Validate;
 use polars::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub mod base;
+pub mod extract;
 pub mod fund_alloc;
 pub mod helpers;
+pub mod inforce;
+pub mod int_rate_curve;
 pub mod load;
 pub mod people;
 pub mod policy;
 pub mod rider;
+pub mod validation;
 pub mod withdrawal;
@@ -1,32 +1,42 @@
 use super::*;
 
 use crate::database::{
-    admin_chrg::get_admin_chrg_lf,
+    admin_chrg::{get_admin_chrg_lf, get_admin_chrg_rule},
     alloc_chrg_rate::{get_ep_alloc_chrg_rate_lf, get_tp_alloc_chrg_rate_lf},
+    amount_limit::{AmountLimit, get_amount_limit},
     coi_rate::get_coi_rate_lf,
     extra_prem_rate::get_extra_prem_rate,
-    int_rate::{get_gir, get_hir, get_lir},
+    int_rate::{get_gir, get_guarantee_period, get_hir, get_int_rate_curve, get_lir},
     juvenile_lien_rate::get_juvenile_lien_rate_lf,
     lb_rate::get_lb_rate_lf,
     modal_factor::get_modal_factor_tuple,
     prem_rate::get_prem_rate,
+    product::{ProductRules, get_age_basis, get_currency, get_product_rules},
+    sb_rate::{SbRule, get_sb_rules},
+    shock::AssumptionShock,
     srr_chrg_rate::get_srr_chrg_rate_lf,
 };
 use crate::structs::{
     fund_alloc::FundAlloc,
-    helpers::{calculate_age, calculate_month_age},
+    helpers::{calculate_age_on_basis, calculate_month_age},
+    inforce::Inforce,
+    int_rate_curve::IntRateCurve,
     load::Load,
     people::Insured,
-    withdrawal::Withdrawal,
+    validation::coded_error,
+    withdrawal::WithdrawalPlan,
 };
 
 // -----------------------------------------------------------------------------
 // PRIVATE
 // -----------------------------------------------------------------------------
-fn fund_alloc_sum_pct_validation(value: &Vec<FundAlloc>, _ctx: &()) -> garde::Result {
+fn fund_alloc_sum_pct_validation(value: &[FundAlloc], _ctx: &()) -> garde::Result {
     // Cannot be empty vec![]
     if value.is_empty() {
-        return Err(garde::Error::new("Fund allocation cannot be empty"));
+        return Err(coded_error(
+            ValidationCodeEnum::FundAllocEmpty,
+            "Fund allocation cannot be empty",
+        ));
     }
 
     // Sum of all fund allocations must equal 100%
@@ -34,13 +44,15 @@ fn fund_alloc_sum_pct_validation(value: &Vec<FundAlloc>, _ctx: &()) -> garde::Re
     let total_ep_pct: f64 = value.iter().map(|fa| fa.ep_pct as f64).sum();
 
     if (total_tp_pct - 100.0).abs() > f64::EPSILON {
-        return Err(garde::Error::new(
+        return Err(coded_error(
+            ValidationCodeEnum::FundAllocTpSum,
             "Total TP fund allocation must equal 100%",
         ));
     }
 
     if (total_ep_pct - 100.0).abs() > f64::EPSILON {
-        return Err(garde::Error::new(
+        return Err(coded_error(
+            ValidationCodeEnum::FundAllocEpSum,
             "Total EP fund allocation must equal 100%",
         ));
     }
@@ -48,43 +60,10 @@ fn fund_alloc_sum_pct_validation(value: &Vec<FundAlloc>, _ctx: &()) -> garde::Re
     Ok(())
 }
 
-fn withdrawal_plan_year_input_validation(
-    value: &Option<Vec<Withdrawal>>,
-    _ctx: &(),
-) -> garde::Result {
-    if value.is_none() {
-        return Ok(());
-    }
-
-    for (i, row) in value.iter().flatten().enumerate() {
-        // 'from' cannot be less than 'to'
-        if row.from > row.to {
-            let err_msg = format!(
-                "Withdrawal 'from' year ({}) cannot be greater than 'to' year ({}).",
-                row.from, row.to
-            );
-            return Err(garde::Error::new(err_msg));
-        }
-
-        // "from" value must be greater than previous "to" value
-        if i > 0 {
-            let prev_row = &value.as_ref().unwrap()[i - 1];
-            if row.from <= prev_row.to {
-                let err_msg = format!(
-                    "Withdrawal 'from' year ({}) must be greater than previous 'to' year ({}).",
-                    row.from, prev_row.to
-                );
-                return Err(garde::Error::new(err_msg));
-            }
-        }
-    }
-    Ok(())
-}
-
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Base {
     pub id: ULEnum,
@@ -123,9 +102,18 @@ pub struct Base {
     #[garde(dive)]
     pub fund_alloc: Vec<FundAlloc>,
 
-    #[garde(custom(withdrawal_plan_year_input_validation))]
     #[garde(dive)]
-    pub withdrawal_plan: Option<Vec<Withdrawal>>,
+    pub withdrawal_plan: Option<WithdrawalPlan>,
+
+    // Overrides the crediting rates of the rate table
+    #[serde(default)]
+    #[garde(dive)]
+    pub int_rate_curve: Option<IntRateCurve>,
+
+    // Values at the valuation date; projects the remaining years only
+    #[serde(default)]
+    #[garde(dive)]
+    pub inforce: Option<Inforce>,
 }
 
 impl Base {
@@ -135,14 +123,31 @@ impl Base {
     // Most values are returned as PolarsResult(LazyFrame), not Result<PolarsResult(LazyFrame)>, because validation occurs before any calculation.
     // This ensures that by the time these methods are called, inputs are already validated,
     // so error handling via Result is unnecessary here and simplifies downstream code.
-    pub fn entry_age(&self) -> PolarsResult<i32> {
-        calculate_age(&self.insured.dob, &self.rcd).map_err(|e| PolarsError::ComputeError(e.into()))
+    pub fn age_basis(&self) -> PolarsResult<AgeBasisEnum> {
+        get_age_basis(&self.id)
+    }
+
+    pub fn currency(&self) -> PolarsResult<CurrencyEnum> {
+        get_currency(&self.id)
+    }
+
+    // Loaded once per run; the methods below that take the rules are called many times
+    pub fn rules(&self) -> PolarsResult<ProductRules> {
+        get_product_rules(&self.id)
     }
 
+    // Entry age on the age basis of the product; term, acc_ben_term and the projected age follow from it
+    pub fn entry_age(&self, rules: &ProductRules) -> PolarsResult<i32> {
+        calculate_age_on_basis(&self.insured.dob, &self.rcd, rules.age_basis)
+            .map_err(|e| PolarsError::ComputeError(e.into()))
+    }
+
+    // Completed months, only for juveniles below one year whatever the age basis
     pub fn entry_month_age(&self) -> PolarsResult<i32> {
-        if self.entry_age()? == 0 {
-            calculate_month_age(&self.insured.dob, &self.rcd)
-                .map_err(|e| polars::prelude::PolarsError::ComputeError(e.into()))
+        let month_age = calculate_month_age(&self.insured.dob, &self.rcd)
+            .map_err(|e| polars::prelude::PolarsError::ComputeError(e.into()))?;
+        if month_age < 12 {
+            Ok(month_age)
         } else {
             // Indicate value is unusable
             Err(PolarsError::ComputeError("Not applicable".into()))
@@ -164,15 +169,23 @@ impl Base {
         }
     }
 
-    pub fn term(&self) -> PolarsResult<i32> {
+    pub fn term(&self, rules: &ProductRules) -> PolarsResult<i32> {
         // The two methos already return PolarsResult<i32>, so we can use the ? operator directly
-        Ok(&self.maturity_age()? - &self.entry_age()?)
+        Ok(self.maturity_age()? - self.entry_age(rules)?)
+    }
+
+    // First projected policy year: 1 for new business, the year starting on the valuation date in force
+    pub fn first_year(&self) -> PolarsResult<i32> {
+        match &self.inforce {
+            Some(inforce) => inforce.first_year(&self.rcd),
+            None => Ok(1),
+        }
     }
 
-    pub fn acc_ben_term(&self) -> PolarsResult<i32> {
+    pub fn acc_ben_term(&self, rules: &ProductRules) -> PolarsResult<i32> {
         match &self.id {
             ULEnum::UVL01 | ULEnum::UVL02 | ULEnum::UVL03 | ULEnum::ILP01 => {
-                Ok(66 - self.entry_age()?)
+                Ok(66 - self.entry_age(rules)?)
             }
             _ => Err(PolarsError::ComputeError("Unsupported product ID".into())),
         }
@@ -185,8 +198,8 @@ impl Base {
         if let Some(withdrawal_plan) = &self.withdrawal_plan {
             for withdrawal in withdrawal_plan.iter() {
                 for year in withdrawal.from..=withdrawal.to {
-                    years.push(year as i32);
-                    amounts.push(withdrawal.amount as f64);
+                    years.push(year);
+                    amounts.push(withdrawal.amount);
                 }
             }
         }
@@ -202,48 +215,60 @@ impl Base {
     // -------------------------------------------------
     // The values that are set from database
     // -------------------------------------------------
-    pub fn extra_prem_rate(&self) -> PolarsResult<f64> {
+    pub fn extra_prem_rate(&self, rules: &ProductRules) -> PolarsResult<f64> {
         let term = &self.load.em_load_term - 1;
-        get_extra_prem_rate(&self.id, &self.insured.gender, &self.entry_age()?, &term)
+        get_extra_prem_rate(
+            &self.id,
+            &self.insured.gender,
+            &self.entry_age(rules)?,
+            &term,
+        )
     }
 
-    pub fn prem_rate(&self) -> PolarsResult<f64> {
-        get_prem_rate(&self.id, &self.insured.gender, &self.entry_age()?)
+    pub fn prem_rate(&self, rules: &ProductRules) -> PolarsResult<f64> {
+        get_prem_rate(&self.id, &self.insured.gender, &self.entry_age(rules)?)
     }
 
     pub fn modal_factor_tuple(&self) -> PolarsResult<(f64, f64, f64, f64)> {
         get_modal_factor_tuple(&self.id)
     }
 
-    pub fn modal_tp_tuple(&self) -> PolarsResult<(f64, f64, f64, f64)> {
+    pub fn modal_tp_tuple(&self, rules: &ProductRules) -> PolarsResult<(f64, f64, f64, f64)> {
         let (f1, f2, f3, f4) = &self.modal_factor_tuple()?;
-        let crude_prem = self.prem_rate()? * self.si / 1000.0;
+        let crude_prem = self.prem_rate(rules)? * self.si / 1000.0;
+        let round = |x: f64| rules.rounding.round(RoundedQuantityEnum::ModalPrem, x);
         let result = (
-            (f1 * crude_prem / 1000.0).ceil() * 1000.0,
-            (f2 * crude_prem / 1000.0).ceil() * 1000.0,
-            (f3 * crude_prem / 1000.0).ceil() * 1000.0,
-            (f4 * crude_prem / 1000.0).ceil() * 1000.0,
+            round(f1 * crude_prem)?,
+            round(f2 * crude_prem)?,
+            round(f3 * crude_prem)?,
+            round(f4 * crude_prem)?,
         );
         Ok(result)
     }
 
-    pub fn modal_ep_tuple(&self) -> PolarsResult<(f64, f64, f64, f64)> {
+    pub fn modal_ep_tuple(&self, rules: &ProductRules) -> PolarsResult<(f64, f64, f64, f64)> {
         let (f1, f2, f3, f4) = &self.modal_factor_tuple()?;
+        let round = |x: f64| rules.rounding.round(RoundedQuantityEnum::ModalPrem, x);
         let result = (
-            (f1 * &self.ep / 1000.0).ceil() * 1000.0,
-            (f2 * &self.ep / 1000.0).ceil() * 1000.0,
-            (f3 * &self.ep / 1000.0).ceil() * 1000.0,
-            (f4 * &self.ep / 1000.0).ceil() * 1000.0,
+            round(f1 * self.ep)?,
+            round(f2 * self.ep)?,
+            round(f3 * self.ep)?,
+            round(f4 * self.ep)?,
         );
         Ok(result)
     }
 
-    pub fn tp_alloc_chrg_rate_lf(&self) -> PolarsResult<LazyFrame> {
-        get_tp_alloc_chrg_rate_lf(&self.id)
+    pub fn tp_alloc_chrg_rate_lf(&self, shock: &AssumptionShock) -> PolarsResult<LazyFrame> {
+        get_tp_alloc_chrg_rate_lf(&self.id, shock)
     }
 
-    pub fn ep_alloc_chrg_rate_lf(&self) -> PolarsResult<LazyFrame> {
-        get_ep_alloc_chrg_rate_lf(&self.id)
+    pub fn ep_alloc_chrg_rate_lf(&self, shock: &AssumptionShock) -> PolarsResult<LazyFrame> {
+        get_ep_alloc_chrg_rate_lf(&self.id, shock)
+    }
+
+    // Range of SI and EP the product accepts
+    pub fn amount_limit(&self) -> PolarsResult<AmountLimit> {
+        get_amount_limit(&self.id)
     }
 
     pub fn srr_chrg_rate_lf(&self) -> PolarsResult<LazyFrame> {
@@ -254,33 +279,128 @@ impl Base {
         get_juvenile_lien_rate_lf(&self.id)
     }
 
-    pub fn int_rate_tuple(&self) -> PolarsResult<(f64, f64, f64)> {
-        let hir = get_hir(&self.id)?;
-        let lir = get_lir(&self.id)?;
-        let gir = match &self.id {
-            ULEnum::UVL01 | ULEnum::UVL02 | ULEnum::UVL03 => get_gir(&self.id)?,
-            _ => 0_f64,
-        };
+    pub fn int_rate_tuple(&self, shock: &AssumptionShock) -> PolarsResult<(f64, f64, f64)> {
+        let hir = get_hir(&self.id, shock)?;
+        let lir = get_lir(&self.id, shock)?;
+        let gir = get_gir(&self.id, shock)?;
         Ok((hir, lir, gir))
     }
 
-    pub fn admin_chrg_lf(&self) -> PolarsResult<LazyFrame> {
-        get_admin_chrg_lf(&self.id)
+    // Declared rate points of the scenario, from the per-policy curve when given, else the rate table
+    pub fn int_rate_points(
+        &self,
+        scenario: IntRateScenarioEnum,
+        shock: &AssumptionShock,
+    ) -> PolarsResult<Vec<(i32, f64)>> {
+        let override_points = self
+            .int_rate_curve
+            .as_ref()
+            .and_then(|curve| curve.points(scenario, &self.rcd));
+
+        let points = match (override_points, scenario) {
+            // The guaranteed rate is contractual, so only the projected rates are shocked
+            (Some(points), IntRateScenarioEnum::Guaranteed) => points,
+            (Some(points), _) => points
+                .into_iter()
+                .map(|(year, rate)| (year, rate + shock.int_rate_shift))
+                .collect(),
+            (None, _) => get_int_rate_curve(&self.id, scenario, shock)?,
+        };
+        Ok(points)
+    }
+
+    // Crediting rate by policy year: the declared rate, floored at the guaranteed rate during the
+    // guarantee period. The Guaranteed scenario declares the guaranteed rate itself.
+    pub fn int_rate_curve_lf(
+        &self,
+        scenario: IntRateScenarioEnum,
+        shock: &AssumptionShock,
+        rules: &ProductRules,
+    ) -> PolarsResult<LazyFrame> {
+        self.credited_int_rate_lf(&self.int_rate_points(scenario, shock)?, rules)
+    }
+
+    // Crediting rate by policy year for any declared rate path
+    pub fn credited_int_rate_lf(
+        &self,
+        declared_points: &[(i32, f64)],
+        rules: &ProductRules,
+    ) -> PolarsResult<LazyFrame> {
+        // Each rate holds until the next point; years before the first point take the first rate
+        let step = |points: &[(i32, f64)], year: &i32| {
+            points
+                .iter()
+                .rev()
+                .find(|(from, _)| from <= year)
+                .or(points.first())
+                .map(|(_, rate)| *rate)
+                .unwrap_or(0.0)
+        };
+        // The guaranteed rate is contractual, so no shock moves the floor
+        let guaranteed_points =
+            self.int_rate_points(IntRateScenarioEnum::Guaranteed, &AssumptionShock::default())?;
+        let guarantee_period = self.guarantee_period(rules)?;
+
+        let years: Vec<i32> = (1..=self.term(rules)?).collect();
+        let declared: Vec<f64> = years.iter().map(|y| step(declared_points, y)).collect();
+        let floor: Vec<Option<f64>> = years
+            .iter()
+            .map(|y| (*y <= guarantee_period).then(|| step(&guaranteed_points, y)))
+            .collect();
+        let credited: Vec<f64> = declared
+            .iter()
+            .zip(&floor)
+            .map(|(rate, floor)| floor.map_or(*rate, |floor| rate.max(floor)))
+            .collect();
+
+        let df = df![
+            "year" => years,
+            "declared_int_rate" => declared,
+            "floor_int_rate" => floor,
+            "annual_int_rate" => credited
+        ]?;
+        Ok(df.lazy())
+    }
+
+    // Years in which the guaranteed rate floors the declared rate
+    pub fn guarantee_period(&self, rules: &ProductRules) -> PolarsResult<i32> {
+        match get_guarantee_period(&self.id)? {
+            Some(guarantee_period) => Ok(guarantee_period),
+            None => self.term(rules),
+        }
+    }
+
+    // Through the last calendar year of the policy term
+    pub fn admin_chrg_lf(
+        &self,
+        shock: &AssumptionShock,
+        rules: &ProductRules,
+    ) -> PolarsResult<LazyFrame> {
+        get_admin_chrg_lf(&self.id, shock, self.rcd.year() + self.term(rules)? - 1)
+    }
+
+    // Charged instead of the projected admin charge in the Guaranteed scenario
+    pub fn max_admin_chrg(&self) -> PolarsResult<Option<f64>> {
+        Ok(get_admin_chrg_rule(&self.id)?.max_admin_chrg)
     }
 
     pub fn lb_rate_lf(&self) -> PolarsResult<LazyFrame> {
         get_lb_rate_lf(&self.id)
     }
 
-    pub fn coi_rate_lf(&self) -> PolarsResult<LazyFrame> {
-        get_coi_rate_lf(&self.id, &self.insured.gender)
+    pub fn sb_rules(&self) -> PolarsResult<Vec<SbRule>> {
+        get_sb_rules(&self.id)
+    }
+
+    pub fn coi_rate_lf(&self, shock: &AssumptionShock) -> PolarsResult<LazyFrame> {
+        get_coi_rate_lf(&self.id, &self.insured.gender, shock)
     }
 
     // -------------------------------------------------
     // The values that are set manually not from database
     // -------------------------------------------------
-    pub fn big_case_bonus(&self) -> PolarsResult<f64> {
-        let (tp, _, _, _) = self.modal_tp_tuple()?;
+    pub fn big_case_bonus(&self, rules: &ProductRules) -> PolarsResult<f64> {
+        let (tp, _, _, _) = self.modal_tp_tuple(rules)?;
 
         match self.id {
             ULEnum::ILP01 if tp >= 100_000_000.0 => Ok(tp * 0.05),
@@ -298,8 +418,8 @@ impl Base {
         }
     }
 
-    pub fn min_pav_after_withdrawal(&self) -> PolarsResult<f64> {
-        let (tp, _, _, _) = self.modal_tp_tuple()?;
+    pub fn min_pav_after_withdrawal(&self, rules: &ProductRules) -> PolarsResult<f64> {
+        let (tp, _, _, _) = self.modal_tp_tuple(rules)?;
 
         match self.id {
             ULEnum::UVL01 | ULEnum::UVL02 | ULEnum::UVL03 | ULEnum::ILP01 => Ok(tp),
@@ -308,10 +428,7 @@ impl Base {
     }
 
     pub fn min_si(&self) -> PolarsResult<f64> {
-        match self.id {
-            ULEnum::UVL01 | ULEnum::UVL02 | ULEnum::UVL03 | ULEnum::ILP01 => Ok(100_000_000.0),
-            _ => Err(PolarsError::ComputeError("Unsupported product ID".into())),
-        }
+        Ok(self.amount_limit()?.min_si)
     }
 
     pub fn withdrawal_start_year(&self) -> PolarsResult<i32> {
@@ -337,33 +454,135 @@ impl Base {
             _ => Err(PolarsError::ComputeError("Unsupported product ID".into())),
         }
     }
-
-    pub fn sb_review_period(&self) -> PolarsResult<i32> {
-        match self.id {
-            ULEnum::UVL01 | ULEnum::UVL02 | ULEnum::UVL03 | ULEnum::ILP01 => Ok(10),
-            _ => Err(PolarsError::ComputeError("Unsupported product ID".into())),
-        }
-    }
 }
 
 // -----------------------------------------------------------------------------
 // UNIT TESTS
 // -----------------------------------------------------------------------------
+#[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::database::shock::AssumptionShock;
+    use crate::enums::{AgeBasisEnum, IntRateScenarioEnum, ULEnum};
     use crate::helpers::read_json_struct;
     use crate::structs::policy::Policy;
+    use chrono::NaiveDate;
 
     #[test]
     fn test_fn_base_struct_01() {
         // Generate result DataFrame from fixed function
         let json_path = "src/cashflows/test_data/uvl01_policy.json";
         let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let rules = policy.base.rules().unwrap();
         // Method modal_tp_tuple()
         assert_eq!(
-            policy.base.modal_tp_tuple().unwrap().0,
+            policy.base.modal_tp_tuple(&rules).unwrap().0,
             1_072_000.0,
             "Failed to get modal TP tuple"
         );
     }
+
+    #[test]
+    fn test_fn_base_struct_02() {
+        // Declared 8% for three years, grading to a long-term 6%
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        policy.base.int_rate_curve = serde_json::from_str(
+            r#"{"basis": "PolicyYear", "high": [{"from": 1, "rate": 0.08}, {"from": 4, "rate": 0.06}]}"#,
+        )
+        .unwrap();
+        let rules = policy.base.rules().unwrap();
+
+        let rates = |scenario| {
+            let df = policy
+                .base
+                .int_rate_curve_lf(scenario, &AssumptionShock::default(), &rules)
+                .unwrap()
+                .collect()
+                .unwrap();
+            let rates = df.column("annual_int_rate").unwrap().f64().unwrap().clone();
+            rates.into_no_null_iter().collect::<Vec<f64>>()
+        };
+        let high = rates(IntRateScenarioEnum::High);
+        assert_eq!(high[..5], [0.08, 0.08, 0.08, 0.06, 0.06]);
+        assert_eq!(high.last(), Some(&0.06));
+        // Scenarios without an override keep the rate table
+        assert_eq!(rates(IntRateScenarioEnum::Low)[0], 0.05);
+    }
+
+    #[test]
+    fn test_fn_base_struct_03() {
+        // Interest -4% takes the Low declared rate to 1%, below the 2% guaranteed floor
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = read_json_struct::<Policy>(json_path).unwrap();
+        let shock = AssumptionShock {
+            int_rate_shift: -0.04,
+            ..Default::default()
+        };
+        let rules = policy.base.rules().unwrap();
+        let term = policy.base.term(&rules).unwrap() as usize;
+
+        let df = policy
+            .base
+            .int_rate_curve_lf(IntRateScenarioEnum::Low, &shock, &rules)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let declared = df.column("declared_int_rate").unwrap().f64().unwrap();
+        let floor = df.column("floor_int_rate").unwrap().f64().unwrap();
+        let credited = df.column("annual_int_rate").unwrap().f64().unwrap();
+        approx::assert_abs_diff_eq!(declared.get(0).unwrap(), 0.01, epsilon = 1e-12);
+        assert_eq!(floor.get(0), Some(0.02));
+        assert_eq!(credited.get(0), Some(0.02));
+
+        // UVL guarantees the floor to maturity
+        assert_eq!(floor.get(term - 1), Some(0.02));
+    }
+
+    #[test]
+    fn test_fn_base_struct_04() {
+        // ILP carries no guarantee, so a negative declared rate is credited as is
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        policy.base.id = ULEnum::ILP01;
+        policy.base.int_rate_curve =
+            serde_json::from_str(r#"{"basis": "PolicyYear", "low": [{"from": 1, "rate": -0.02}]}"#)
+                .unwrap();
+        let rules = policy.base.rules().unwrap();
+
+        let df = policy
+            .base
+            .int_rate_curve_lf(
+                IntRateScenarioEnum::Low,
+                &AssumptionShock::default(),
+                &rules,
+            )
+            .unwrap()
+            .collect()
+            .unwrap();
+        // One row per year of the policy term
+        let term = policy.base.term(&rules).unwrap() as usize;
+        assert_eq!(df.height(), term);
+        assert_eq!(df.column("floor_int_rate").unwrap().null_count(), term);
+        let credited = df.column("annual_int_rate").unwrap().f64().unwrap();
+        assert_eq!(credited.get(0), Some(-0.02));
+    }
+
+    #[test]
+    fn test_fn_base_struct_05() {
+        // UVL01 rates on age last birthday, and the term runs from that age to maturity
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let mut policy = read_json_struct::<Policy>(json_path).unwrap();
+        assert_eq!(policy.base.age_basis().unwrap(), AgeBasisEnum::LastBirthday);
+
+        // Born 29 Feb, the day before the 1 March birthday of a non-leap year
+        policy.base.insured.dob = NaiveDate::from_ymd_opt(2000, 2, 29).unwrap();
+        policy.base.rcd = NaiveDate::from_ymd_opt(2025, 2, 28).unwrap();
+        let base = &policy.base;
+        let rules = base.rules().unwrap();
+        assert_eq!(base.entry_age(&rules).unwrap(), 24);
+        assert_eq!(
+            base.term(&rules).unwrap(),
+            base.maturity_age().unwrap() - base.entry_age(&rules).unwrap()
+        );
+    }
 }
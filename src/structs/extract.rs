@@ -0,0 +1,883 @@
+use super::*;
+use crate::structs::policy::Policy;
+use crate::structs::validation::ValidationIssue;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+#[derive(Clone, Copy)]
+enum Kind {
+    Text,
+    Date,
+    Float,
+    Int,
+    Enum(fn(&str) -> bool),
+}
+
+// Row a field is read from: the policy fields are read from the base row
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    Policy,
+    Coverage,
+    Base,
+    Rider,
+}
+
+struct FieldSpec {
+    field: &'static str, // Logical name, also the default column name
+    path: &'static str,  // Within the policy for Scope::Policy, else within the coverage
+    kind: Kind,
+    scope: Scope,
+    required: bool,
+}
+
+#[rustfmt::skip]
+const FIELDS: [FieldSpec; 32] = [
+    FieldSpec { field: "policy_id", path: "id", kind: Kind::Text, scope: Scope::Policy, required: true },
+    FieldSpec { field: "created_date", path: "created_date", kind: Kind::Date, scope: Scope::Policy, required: true },
+    FieldSpec { field: "owner_id", path: "owner/id", kind: Kind::Text, scope: Scope::Policy, required: true },
+    FieldSpec { field: "owner_ssn", path: "owner/ssn", kind: Kind::Text, scope: Scope::Policy, required: true },
+    FieldSpec { field: "owner_dob", path: "owner/dob", kind: Kind::Date, scope: Scope::Policy, required: true },
+    FieldSpec { field: "coverage", path: "id", kind: Kind::Text, scope: Scope::Coverage, required: true },
+    FieldSpec { field: "rcd", path: "rcd", kind: Kind::Date, scope: Scope::Coverage, required: true },
+    FieldSpec { field: "paymode", path: "paymode", kind: Kind::Enum(|v| PayModeEnum::from_str(v).is_ok()), scope: Scope::Coverage, required: true },
+    FieldSpec { field: "channel", path: "channel", kind: Kind::Enum(|v| ChannelEnum::from_str(v).is_ok()), scope: Scope::Coverage, required: true },
+    FieldSpec { field: "status", path: "status", kind: Kind::Enum(|v| StatusEnum::from_str(v).is_ok()), scope: Scope::Coverage, required: true },
+    FieldSpec { field: "insured_id", path: "insured/id", kind: Kind::Text, scope: Scope::Coverage, required: true },
+    FieldSpec { field: "insured_ssn", path: "insured/ssn", kind: Kind::Text, scope: Scope::Coverage, required: true },
+    FieldSpec { field: "insured_dob", path: "insured/dob", kind: Kind::Date, scope: Scope::Coverage, required: true },
+    FieldSpec { field: "insured_gender", path: "insured/gender", kind: Kind::Enum(|v| GenderEnum::from_str(v).is_ok()), scope: Scope::Coverage, required: true },
+    FieldSpec { field: "insured_relatsh", path: "insured/relatsh", kind: Kind::Enum(|v| RelationshipToOwnerEnum::from_str(v).is_ok()), scope: Scope::Coverage, required: true },
+    FieldSpec { field: "si", path: "si", kind: Kind::Float, scope: Scope::Coverage, required: false },
+    FieldSpec { field: "em_load", path: "load/em_load", kind: Kind::Float, scope: Scope::Coverage, required: false },
+    FieldSpec { field: "em_load_term", path: "load/em_load_term", kind: Kind::Int, scope: Scope::Coverage, required: false },
+    FieldSpec { field: "pm_load", path: "load/pm_load", kind: Kind::Int, scope: Scope::Coverage, required: false },
+    FieldSpec { field: "pm_load_term", path: "load/pm_load_term", kind: Kind::Int, scope: Scope::Coverage, required: false },
+    FieldSpec { field: "opted_tp_term", path: "opted_tp_term", kind: Kind::Int, scope: Scope::Base, required: true },
+    FieldSpec { field: "ep", path: "ep", kind: Kind::Float, scope: Scope::Base, required: true },
+    FieldSpec { field: "opted_ep_term", path: "opted_ep_term", kind: Kind::Int, scope: Scope::Base, required: true },
+    FieldSpec { field: "death_tpd_option", path: "death_tpd_option", kind: Kind::Enum(|v| DeathTPDBenefitEnum::from_str(v).is_ok()), scope: Scope::Base, required: true },
+    FieldSpec { field: "maturity_option", path: "maturity_option", kind: Kind::Int, scope: Scope::Base, required: true },
+    FieldSpec { field: "acc_ben_coeff", path: "acc_ben_coeff", kind: Kind::Int, scope: Scope::Base, required: true },
+    FieldSpec { field: "int_rate_basis", path: "int_rate_curve/basis", kind: Kind::Enum(|v| YearBasisEnum::from_str(v).is_ok()), scope: Scope::Base, required: false },
+    FieldSpec { field: "valuation_date", path: "inforce/valuation_date", kind: Kind::Date, scope: Scope::Base, required: false },
+    FieldSpec { field: "inforce_si", path: "inforce/si", kind: Kind::Float, scope: Scope::Base, required: false },
+    FieldSpec { field: "inforce_eav", path: "inforce/eav", kind: Kind::Float, scope: Scope::Base, required: false },
+    FieldSpec { field: "inforce_tav", path: "inforce/tav", kind: Kind::Float, scope: Scope::Base, required: false },
+    FieldSpec { field: "hop2_option", path: "hop2_option", kind: Kind::Int, scope: Scope::Rider, required: false },
+];
+
+// List of the base read from a column group repeated with a suffix, e.g. fund, fund_2, fund_3: one element per suffix.
+// An element gives every field of the group; the fields are within the element.
+struct ListSpec {
+    path: &'static str,
+    items: &'static [FieldSpec],
+}
+
+#[rustfmt::skip]
+const LISTS: [ListSpec; 5] = [
+    ListSpec { path: "fund_alloc", items: &[
+        FieldSpec { field: "fund", path: "fund", kind: Kind::Enum(|v| FundEnum::from_str(v).is_ok()), scope: Scope::Base, required: true },
+        FieldSpec { field: "fund_tp_pct", path: "tp_pct", kind: Kind::Int, scope: Scope::Base, required: true },
+        FieldSpec { field: "fund_ep_pct", path: "ep_pct", kind: Kind::Int, scope: Scope::Base, required: true },
+    ] },
+    ListSpec { path: "withdrawal_plan", items: &[
+        FieldSpec { field: "withdrawal_from", path: "from", kind: Kind::Int, scope: Scope::Base, required: true },
+        FieldSpec { field: "withdrawal_to", path: "to", kind: Kind::Int, scope: Scope::Base, required: true },
+        FieldSpec { field: "withdrawal_amount", path: "amount", kind: Kind::Float, scope: Scope::Base, required: true },
+    ] },
+    ListSpec { path: "int_rate_curve/high", items: &[
+        FieldSpec { field: "int_rate_high_from", path: "from", kind: Kind::Int, scope: Scope::Base, required: true },
+        FieldSpec { field: "int_rate_high_rate", path: "rate", kind: Kind::Float, scope: Scope::Base, required: true },
+    ] },
+    ListSpec { path: "int_rate_curve/low", items: &[
+        FieldSpec { field: "int_rate_low_from", path: "from", kind: Kind::Int, scope: Scope::Base, required: true },
+        FieldSpec { field: "int_rate_low_rate", path: "rate", kind: Kind::Float, scope: Scope::Base, required: true },
+    ] },
+    ListSpec { path: "int_rate_curve/guaranteed", items: &[
+        FieldSpec { field: "int_rate_guaranteed_from", path: "from", kind: Kind::Int, scope: Scope::Base, required: true },
+        FieldSpec { field: "int_rate_guaranteed_rate", path: "rate", kind: Kind::Float, scope: Scope::Base, required: true },
+    ] },
+];
+
+// Column of a list field in an element: the mapped column, suffixed from the second element on
+fn _slot_column(mapping: &ExtractMapping, field: &str, slot: usize) -> String {
+    match slot {
+        1 => mapping.column(field).to_string(),
+        _ => format!("{}_{}", mapping.column(field), slot),
+    }
+}
+
+// Suffixes of a list the extract has columns for, from 1 up to the first missing one
+fn _slots(df: &DataFrame, mapping: &ExtractMapping, list: &ListSpec) -> Vec<usize> {
+    (1..)
+        .take_while(|slot| {
+            list.items
+                .iter()
+                .any(|item| df.column(&_slot_column(mapping, item.field, *slot)).is_ok())
+        })
+        .collect()
+}
+
+// Optional group of fields given in full or not at all: the rider load, the in-force values and the rate overrides
+fn _optional_group(path: &str, is_base: bool) -> Option<&str> {
+    match path.split_once('/') {
+        Some(("load", _)) if !is_base => Some("load"),
+        Some(("inforce", _)) => Some("inforce"),
+        Some(("int_rate_curve", _)) => Some("int_rate_curve"),
+        _ => None,
+    }
+}
+
+fn _applies(spec: &FieldSpec, is_base: bool) -> bool {
+    match spec.scope {
+        Scope::Policy | Scope::Base => is_base,
+        Scope::Rider => !is_base,
+        Scope::Coverage => true,
+    }
+}
+
+fn _trimmed(values: &Column, i: usize) -> PolarsResult<Option<String>> {
+    Ok(values
+        .str()?
+        .get(i)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string))
+}
+
+fn _parse(spec: &FieldSpec, raw: &str) -> Option<Value> {
+    match spec.kind {
+        Kind::Text => Some(json!(raw)),
+        Kind::Date => chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .ok()
+            .map(|_| json!(raw)),
+        Kind::Float => raw.parse::<f64>().ok().map(|v| json!(v)),
+        Kind::Int => raw
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.fract() == 0.0)
+            .map(|v| json!(v as i64)),
+        Kind::Enum(is_valid) => is_valid(raw).then(|| json!(raw)),
+    }
+}
+
+fn _set_path(target: &mut Value, path: &str, value: Value) {
+    let mut node = target;
+    let mut keys = path.split('/').peekable();
+    while let Some(key) = keys.next() {
+        let map = node
+            .as_object_mut()
+            .expect("Paths only run through objects");
+        if keys.peek().is_none() {
+            map.insert(key.to_string(), value);
+            return;
+        }
+        node = map.entry(key).or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+// One record of the extract, read into the JSON of its coverage
+struct Row {
+    line: usize,
+    policy_id: String,
+    is_base: bool,
+    policy_fields: Value,
+    coverage: Value,
+    // Suffix of each list element, by list path, to trace issues back to their columns
+    slots: HashMap<&'static str, Vec<usize>>,
+}
+
+// Policy under assembly, with the line of each coverage for the issues
+struct PolicyRows {
+    policy_id: String,
+    base: Option<Row>,
+    riders: Vec<Row>,
+    issues: Vec<ExtractIssue>,
+}
+
+fn _issue(
+    line: usize,
+    policy_id: &str,
+    column: Option<&str>,
+    issue: ValidationIssue,
+) -> ExtractIssue {
+    ExtractIssue {
+        line,
+        policy_id: policy_id.to_string(),
+        column: column.map(str::to_string),
+        issue,
+    }
+}
+
+// The raw values of a record, keyed by logical field; empty cells are missing
+fn _raw_values(
+    df: &DataFrame,
+    mapping: &ExtractMapping,
+    i: usize,
+) -> PolarsResult<HashMap<&'static str, String>> {
+    let mut raw = HashMap::new();
+    for spec in FIELDS.iter() {
+        // Columns the extract does not have are missing too
+        let Ok(values) = df.column(mapping.column(spec.field)) else {
+            continue;
+        };
+        if let Some(value) = _trimmed(values, i)? {
+            raw.insert(spec.field, value);
+        }
+    }
+    Ok(raw)
+}
+
+// Reads the lists of a base record into its coverage and returns the suffix of each element.
+// Elements left empty are skipped, so records may give different numbers of funds, withdrawals or rate points.
+fn _read_lists(
+    df: &DataFrame,
+    mapping: &ExtractMapping,
+    i: usize,
+    line: usize,
+    policy_id: &str,
+    coverage: &mut Value,
+    issues: &mut Vec<ExtractIssue>,
+) -> PolarsResult<HashMap<&'static str, Vec<usize>>> {
+    use ValidationCodeEnum as Code;
+
+    let mut slots = HashMap::new();
+    for list in LISTS.iter() {
+        let mut filled: Vec<(usize, Vec<Option<String>>)> = Vec::new();
+        for slot in _slots(df, mapping, list) {
+            let mut raw = Vec::new();
+            for item in list.items {
+                let raw_value = match df.column(&_slot_column(mapping, item.field, slot)) {
+                    Ok(values) => _trimmed(values, i)?,
+                    Err(_) => None,
+                };
+                raw.push(raw_value);
+            }
+            if raw.iter().any(Option::is_some) {
+                filled.push((slot, raw));
+            }
+        }
+
+        // A single fund given without its shares takes the whole premium
+        if list.path == "fund_alloc"
+            && let [(_, raw)] = filled.as_mut_slice()
+            && raw[0].is_some()
+            && raw[1..].iter().all(Option::is_none)
+        {
+            raw[1] = Some("100".to_string());
+            raw[2] = Some("100".to_string());
+        }
+
+        let mut elements = Vec::new();
+        for (index, (slot, raw)) in filled.iter().enumerate() {
+            let mut element = json!({});
+            for (item, raw_value) in list.items.iter().zip(raw) {
+                let column = _slot_column(mapping, item.field, *slot);
+                let pointer = format!("/base/{}/{}/{}", list.path, index, item.path);
+                let Some(value) = raw_value else {
+                    let err_msg = format!("Column '{}' has no value.", column);
+                    let params = json!({ "column": column });
+                    let issue =
+                        ValidationIssue::error(Code::MissingValue, &pointer, err_msg, params);
+                    issues.push(_issue(line, policy_id, Some(&column), issue));
+                    continue;
+                };
+                let Some(parsed) = _parse(item, value) else {
+                    let err_msg = format!("Column '{}' has an invalid value '{}'.", column, value);
+                    let params = json!({ "column": column, "value": value });
+                    let issue =
+                        ValidationIssue::error(Code::InvalidValue, &pointer, err_msg, params);
+                    issues.push(_issue(line, policy_id, Some(&column), issue));
+                    continue;
+                };
+                _set_path(&mut element, item.path, parsed);
+            }
+            elements.push(element);
+        }
+        if !elements.is_empty() {
+            _set_path(coverage, list.path, Value::Array(elements));
+            slots.insert(list.path, filled.iter().map(|(slot, _)| *slot).collect());
+        }
+    }
+    Ok(slots)
+}
+
+fn _read_row(
+    df: &DataFrame,
+    mapping: &ExtractMapping,
+    i: usize,
+    line: usize,
+) -> PolarsResult<Result<Row, Vec<ExtractIssue>>> {
+    use ValidationCodeEnum as Code;
+
+    let raw = _raw_values(df, mapping, i)?;
+    let policy_id = raw.get("policy_id").cloned().unwrap_or_default();
+    let coverage = raw.get("coverage").map(String::as_str).unwrap_or_default();
+    let is_base = ULEnum::from_str(coverage).is_ok();
+    let is_rider = RiderEnum::from_str(coverage).is_ok();
+    let coverage_pointer = if is_base { "/base" } else { "/rider" };
+
+    let mut issues = Vec::new();
+    // Rows are grouped by policy, so every row needs its id
+    if policy_id.is_empty() {
+        let column = mapping.column("policy_id");
+        let err_msg = format!("Column '{}' has no value.", column);
+        let params = json!({ "column": column });
+        let issue = ValidationIssue::error(Code::MissingValue, "/id", err_msg, params);
+        issues.push(_issue(line, &policy_id, Some(column), issue));
+        return Ok(Err(issues));
+    }
+    if !is_base && !is_rider {
+        let column = mapping.column("coverage");
+        let err_msg = format!("Coverage '{}' is neither a product nor a rider.", coverage);
+        let params = json!({ "value": coverage });
+        let issue = ValidationIssue::error(Code::InvalidValue, coverage_pointer, err_msg, params);
+        issues.push(_issue(line, &policy_id, Some(column), issue));
+        return Ok(Err(issues));
+    }
+
+    let mut policy_fields = json!({});
+    let mut coverage = json!({});
+    let slots = match is_base {
+        true => _read_lists(df, mapping, i, line, &policy_id, &mut coverage, &mut issues)?,
+        false => HashMap::new(),
+    };
+    let groups_present: Vec<&str> = FIELDS
+        .iter()
+        .filter(|spec| _applies(spec, is_base) && raw.contains_key(spec.field))
+        .map(|spec| spec.path)
+        .chain(slots.keys().copied())
+        .filter_map(|path| _optional_group(path, is_base))
+        .collect();
+
+    for spec in FIELDS.iter().filter(|spec| _applies(spec, is_base)) {
+        let column = mapping.column(spec.field);
+        let pointer = match spec.scope {
+            Scope::Policy => format!("/{}", spec.path),
+            _ => format!("{}/{}", coverage_pointer, spec.path),
+        };
+        // The base always has its SI and load
+        let required = spec.required
+            || (is_base && (spec.path == "si" || spec.path.starts_with("load/")))
+            || _optional_group(spec.path, is_base).is_some_and(|g| groups_present.contains(&g));
+
+        let Some(value) = raw.get(spec.field) else {
+            if required {
+                let err_msg = format!("Column '{}' has no value.", column);
+                let params = json!({ "column": column });
+                let issue = ValidationIssue::error(Code::MissingValue, &pointer, err_msg, params);
+                issues.push(_issue(line, &policy_id, Some(column), issue));
+            }
+            continue;
+        };
+        let Some(parsed) = _parse(spec, value) else {
+            let err_msg = format!("Column '{}' has an invalid value '{}'.", column, value);
+            let params = json!({ "column": column, "value": value });
+            let issue = ValidationIssue::error(Code::InvalidValue, &pointer, err_msg, params);
+            issues.push(_issue(line, &policy_id, Some(column), issue));
+            continue;
+        };
+        match spec.scope {
+            Scope::Policy => _set_path(&mut policy_fields, spec.path, parsed),
+            _ => _set_path(&mut coverage, spec.path, parsed),
+        }
+    }
+
+    if !issues.is_empty() {
+        return Ok(Err(issues));
+    }
+
+    // Without a fund the default fund takes the whole premium
+    if is_base && coverage.get("fund_alloc").is_none() {
+        let fund = FundEnum::F000.as_ref();
+        coverage["fund_alloc"] = json!([{ "fund": fund, "tp_pct": 100, "ep_pct": 100 }]);
+    }
+    Ok(Ok(Row {
+        line,
+        policy_id,
+        is_base,
+        policy_fields,
+        coverage,
+        slots,
+    }))
+}
+
+// Column of an issue within a list of the base: the column of its element and field, else the first column of the list
+fn _list_column(base: Option<&Row>, mapping: &ExtractMapping, path: &str) -> Option<String> {
+    let list = LISTS.iter().find(|list| {
+        path.strip_prefix(list.path)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })?;
+    let rest = path[list.path.len()..].trim_start_matches('/');
+    let (index, item_path) = rest.split_once('/').unwrap_or((rest, ""));
+    let slot = index
+        .parse::<usize>()
+        .ok()
+        .and_then(|i| base?.slots.get(list.path)?.get(i).copied())
+        .unwrap_or(1);
+    let item = list
+        .items
+        .iter()
+        .find(|item| item.path == item_path)
+        .unwrap_or(&list.items[0]);
+    Some(_slot_column(mapping, item.field, slot))
+}
+
+// Source row and column of a validation issue, from its pointer
+fn _locate(rows: &PolicyRows, mapping: &ExtractMapping, pointer: &str) -> (usize, Option<String>) {
+    let base_line = rows.base.as_ref().map_or(0, |row| row.line);
+    let (line, path, is_coverage) = match pointer.strip_prefix("/rider/") {
+        Some(rest) => {
+            let (index, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let line = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| rows.riders.get(i))
+                .map_or(base_line, |row| row.line);
+            (line, path, true)
+        }
+        None => match pointer.strip_prefix("/base") {
+            Some(path) => (base_line, path.trim_start_matches('/'), true),
+            None => (base_line, pointer.trim_start_matches('/'), false),
+        },
+    };
+    if is_coverage && let Some(column) = _list_column(rows.base.as_ref(), mapping, path) {
+        return (line, Some(column));
+    }
+    let column = FIELDS
+        .iter()
+        .find(|spec| spec.path == path && (spec.scope != Scope::Policy) == is_coverage)
+        .map(|spec| mapping.column(spec.field).to_string());
+    (line, column)
+}
+
+fn _assemble(rows: PolicyRows, mapping: &ExtractMapping) -> (Option<Policy>, Vec<ExtractIssue>) {
+    use ValidationCodeEnum as Code;
+
+    let mut issues = rows.issues.clone();
+    if issues.iter().any(|issue| issue.issue.is_error()) {
+        return (None, issues);
+    }
+    let Some(base) = &rows.base else {
+        let line = rows.riders.first().map_or(0, |row| row.line);
+        let err_msg = format!("Policy {} has no base coverage row.", rows.policy_id);
+        let params = json!({ "policy_id": rows.policy_id });
+        let issue = ValidationIssue::error(Code::MissingBase, "/base", err_msg, params);
+        issues.push(_issue(line, &rows.policy_id, None, issue));
+        return (None, issues);
+    };
+
+    let mut json = base.policy_fields.clone();
+    json["base"] = base.coverage.clone();
+    json["rider"] = match rows.riders.is_empty() {
+        true => Value::Null,
+        false => Value::Array(rows.riders.iter().map(|row| row.coverage.clone()).collect()),
+    };
+    let policy: Policy = match serde_json::from_value(json) {
+        Ok(policy) => policy,
+        Err(e) => {
+            let err_msg = format!("Policy {} cannot be read: {}", rows.policy_id, e);
+            let params = json!({ "detail": e.to_string() });
+            let issue = ValidationIssue::error(Code::InvalidValue, "", err_msg, params);
+            issues.push(_issue(base.line, &rows.policy_id, None, issue));
+            return (None, issues);
+        }
+    };
+
+    for issue in policy.validation_issues() {
+        let (line, column) = _locate(&rows, mapping, &issue.pointer);
+        issues.push(_issue(line, &rows.policy_id, column.as_deref(), issue));
+    }
+    match issues.iter().any(|issue| issue.issue.is_error()) {
+        true => (None, issues),
+        false => (Some(policy), issues),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Column of the extract for each field, where it differs from the field name,
+/// e.g. `{"si": "SUM_ASSURED", "rcd": "RISK_COMM_DATE"}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExtractMapping(pub HashMap<String, String>);
+
+impl ExtractMapping {
+    pub fn column<'a>(&'a self, field: &'a str) -> &'a str {
+        self.0.get(field).map_or(field, String::as_str)
+    }
+
+    // Every field the extract can carry, in column order; the list fields take a suffix from their second element on
+    pub fn fields() -> Vec<&'static str> {
+        let list_fields = LISTS.iter().flat_map(|list| list.items.iter());
+        FIELDS
+            .iter()
+            .chain(list_fields)
+            .map(|spec| spec.field)
+            .collect()
+    }
+}
+
+// Issue of an extract record: its line, counting the header as line 1 for CSV, and its source column
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExtractIssue {
+    pub line: usize,
+    pub policy_id: String,
+    pub column: Option<String>,
+    #[serde(flatten)]
+    pub issue: ValidationIssue,
+}
+
+#[derive(Debug, Clone)]
+pub struct Extract {
+    // Policies without errors, in the order of their first row
+    pub policies: Vec<Policy>,
+    // Errors of the rejected policies and warnings of the accepted ones
+    pub issues: Vec<ExtractIssue>,
+}
+
+/// Reads a one-row-per-coverage extract into policies.
+///
+/// Rows are grouped by `policy_id`. The coverage is the product of the base row or the rider of a rider row;
+/// the policy and owner fields are read from the base row. Every policy is validated, and a policy
+/// with any error is rejected with issues pointing at the line and column of the extract.
+/// `first_line` is the line of the first record, 2 for a CSV with a header.
+pub fn read_extract_df(
+    df: &DataFrame,
+    mapping: &ExtractMapping,
+    first_line: usize,
+) -> PolarsResult<Extract> {
+    let fields = ExtractMapping::fields();
+    for field in mapping.0.keys() {
+        if !fields.contains(&field.as_str()) {
+            let err_msg = format!("Unknown extract field '{}' in the column mapping", field);
+            return Err(PolarsError::ComputeError(err_msg.into()));
+        }
+    }
+    let policy_id_column = mapping.column("policy_id");
+    if df.column(policy_id_column).is_err() {
+        let err_msg = format!("Extract has no policy id column '{}'", policy_id_column);
+        return Err(PolarsError::ColumnNotFound(err_msg.into()));
+    }
+
+    // Every value is read as text, then parsed by field
+    let df = df
+        .clone()
+        .lazy()
+        .with_columns([all().cast(DataType::String)])
+        .collect()?;
+
+    let mut grouped: Vec<PolicyRows> = Vec::new();
+    for i in 0..df.height() {
+        let line = first_line + i;
+        let row = _read_row(&df, mapping, i, line)?;
+        let policy_id = match &row {
+            Ok(row) => row.policy_id.clone(),
+            Err(issues) => issues[0].policy_id.clone(),
+        };
+        let index = match grouped.iter().position(|p| p.policy_id == policy_id) {
+            Some(index) => index,
+            None => {
+                grouped.push(PolicyRows {
+                    policy_id,
+                    base: None,
+                    riders: Vec::new(),
+                    issues: Vec::new(),
+                });
+                grouped.len() - 1
+            }
+        };
+        let rows = &mut grouped[index];
+        match row {
+            Ok(row) if row.is_base && rows.base.is_some() => {
+                let err_msg = format!("Policy {} has more than one base row.", row.policy_id);
+                let params = json!({ "policy_id": row.policy_id });
+                let issue = ValidationIssue::error(
+                    ValidationCodeEnum::DuplicateBase,
+                    "/base",
+                    err_msg,
+                    params,
+                );
+                let column = Some(mapping.column("coverage"));
+                rows.issues
+                    .push(_issue(row.line, &row.policy_id, column, issue));
+            }
+            Ok(row) if row.is_base => rows.base = Some(row),
+            Ok(row) => rows.riders.push(row),
+            Err(issues) => rows.issues.extend(issues),
+        }
+    }
+
+    let mut extract = Extract {
+        policies: Vec::new(),
+        issues: Vec::new(),
+    };
+    for rows in grouped {
+        let (policy, issues) = _assemble(rows, mapping);
+        extract.policies.extend(policy);
+        extract.issues.extend(issues);
+    }
+    extract.issues.sort_by_key(|issue| issue.line);
+    Ok(extract)
+}
+
+/// Reads a CSV or parquet extract file, by its extension.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_extract(path: &str, mapping: &ExtractMapping) -> PolarsResult<Extract> {
+    let (df, first_line) = match std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        // Read as text so ids keep their leading zeros
+        Some("csv") => {
+            let df = CsvReadOptions::default()
+                .with_has_header(true)
+                .with_infer_schema_length(Some(0))
+                .try_into_reader_with_file_path(Some(path.into()))?
+                .finish()?;
+            (df, 2)
+        }
+        Some("parquet") => {
+            let file = std::fs::File::open(path)?;
+            (ParquetReader::new(file).finish()?, 1)
+        }
+        _ => {
+            let err_msg = format!("Extract {} must be a .csv or .parquet file", path);
+            return Err(PolarsError::ComputeError(err_msg.into()));
+        }
+    };
+    read_extract_df(&df, mapping, first_line)
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "src/structs/test_data/inforce_extract.csv";
+
+    fn issues_at(extract: &Extract, line: usize) -> Vec<&ExtractIssue> {
+        extract.issues.iter().filter(|i| i.line == line).collect()
+    }
+
+    #[test]
+    fn test_fn_read_extract_01() {
+        // P001 is read whole; the others are rejected at their offending lines
+        let extract = read_extract(PATH, &ExtractMapping::default()).unwrap();
+        let ids: Vec<&str> = extract.policies.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["P001"]);
+
+        let policy = &extract.policies[0];
+        let riders = policy.rider.as_ref().unwrap();
+        assert_eq!(riders.len(), 2);
+        assert_eq!(riders[1].hop2_option, Some(4));
+        assert!(riders[1].load.is_none());
+        assert_eq!(policy.base.fund_alloc[0].tp_pct, 100);
+
+        // P002: misspelt paymode on its base row
+        let issues = issues_at(&extract, 5);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].column.as_deref(), Some("paymode"));
+        assert_eq!(issues[0].issue.code, ValidationCodeEnum::InvalidValue);
+        assert!(issues_at(&extract, 6).is_empty());
+
+        // P003: riders without a base
+        assert_eq!(
+            issues_at(&extract, 7)[0].issue.code,
+            ValidationCodeEnum::MissingBase
+        );
+
+        // P004: the zero rider SI is reported on the rider row
+        let issues = issues_at(&extract, 9);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].issue.code, ValidationCodeEnum::SiOutOfRange);
+        assert_eq!(issues[0].issue.pointer, "/rider/0/si");
+        assert_eq!(issues[0].column.as_deref(), Some("si"));
+    }
+
+    #[test]
+    fn test_fn_read_extract_02() {
+        // Renamed columns are read through the mapping, and parquet records are numbered from 1
+        let mut df = CsvReadOptions::default()
+            .with_infer_schema_length(Some(0))
+            .try_into_reader_with_file_path(Some(PATH.into()))
+            .unwrap()
+            .finish()
+            .unwrap();
+        df.rename("si", "SUM_ASSURED".into()).unwrap();
+        df.rename("em_load", "EM".into()).unwrap();
+        let path = std::env::temp_dir().join("si_inforce_extract.parquet");
+        let mut file = std::fs::File::create(&path).unwrap();
+        ParquetWriter::new(&mut file).finish(&mut df).unwrap();
+        let mapping: ExtractMapping =
+            serde_json::from_str(r#"{"si": "SUM_ASSURED", "em_load": "EM"}"#).unwrap();
+
+        let extract = read_extract(path.to_str().unwrap(), &mapping).unwrap();
+        assert_eq!(extract.policies.len(), 1);
+        assert_eq!(extract.policies[0].base.si, 101_000_000.0);
+        let issue = extract
+            .issues
+            .iter()
+            .find(|i| i.policy_id == "P004" && i.issue.is_error())
+            .unwrap();
+        assert_eq!(
+            (issue.line, issue.column.as_deref()),
+            (8, Some("SUM_ASSURED"))
+        );
+
+        // Without the mapping the SI is missing on every row
+        let extract = read_extract_df(&df, &ExtractMapping::default(), 1).unwrap();
+        assert!(extract.policies.is_empty());
+        assert!(
+            extract
+                .issues
+                .iter()
+                .any(|i| i.issue.code == ValidationCodeEnum::MissingValue)
+        );
+
+        let mapping: ExtractMapping = serde_json::from_str(r#"{"sum": "SUM_ASSURED"}"#).unwrap();
+        assert!(read_extract_df(&df, &mapping, 1).is_err());
+    }
+
+    // P001 as an ILP01, which may split its premium across funds, with the columns given set on its base row
+    fn p001_with(columns: &[(&str, &str)]) -> DataFrame {
+        let mut df = CsvReadOptions::default()
+            .with_infer_schema_length(Some(0))
+            .try_into_reader_with_file_path(Some(PATH.into()))
+            .unwrap()
+            .finish()
+            .unwrap()
+            .head(Some(3));
+        for (name, value) in [("coverage", "ILP01")].iter().chain(columns) {
+            let mut values: Vec<Option<String>> = match df.column(name) {
+                Ok(column) => column
+                    .str()
+                    .unwrap()
+                    .iter()
+                    .map(|v| v.map(str::to_string))
+                    .collect(),
+                Err(_) => vec![None; 3],
+            };
+            values[0] = Some(value.to_string());
+            df.with_column(Column::new((*name).into(), values)).unwrap();
+        }
+        df
+    }
+
+    #[test]
+    fn test_fn_read_extract_03() {
+        // Funds, withdrawals and rate points repeat their columns with a suffix from the second on
+        let df = p001_with(&[
+            ("fund", "F001"),
+            ("fund_tp_pct", "60"),
+            ("fund_ep_pct", "100"),
+            ("fund_2", "F002"),
+            ("fund_tp_pct_2", "40"),
+            ("fund_ep_pct_2", "0"),
+            ("withdrawal_from", "5"),
+            ("withdrawal_to", "7"),
+            ("withdrawal_amount", "1000000"),
+            ("withdrawal_from_2", ""),
+            ("withdrawal_to_2", ""),
+            ("withdrawal_amount_2", ""),
+            ("withdrawal_from_3", "12"),
+            ("withdrawal_to_3", "15"),
+            ("withdrawal_amount_3", "5000000"),
+            ("int_rate_basis", "PolicyYear"),
+            ("int_rate_high_from", "1"),
+            ("int_rate_high_rate", "0.06"),
+        ]);
+        let extract = read_extract_df(&df, &ExtractMapping::default(), 2).unwrap();
+        assert_eq!(extract.policies.len(), 1, "{:?}", extract.issues);
+
+        let base = &extract.policies[0].base;
+        let funds: Vec<_> = base
+            .fund_alloc
+            .iter()
+            .map(|fa| (fa.fund, fa.tp_pct, fa.ep_pct))
+            .collect();
+        assert_eq!(
+            funds,
+            vec![(FundEnum::F001, 60, 100), (FundEnum::F002, 40, 0)]
+        );
+        // The empty second withdrawal is skipped
+        let plan: Vec<_> = base
+            .withdrawal_plan
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|w| (w.from, w.to))
+            .collect();
+        assert_eq!(plan, vec![(5, 7), (12, 15)]);
+        let curve = base.int_rate_curve.as_ref().unwrap();
+        assert_eq!(curve.high.as_ref().unwrap()[0].rate, 0.06);
+        assert!(curve.low.is_none());
+    }
+
+    #[test]
+    fn test_fn_read_extract_04() {
+        // Issues of a list land on the column of the element, or on its first column for the list as a whole
+        let columns = |extract: &Extract| -> Vec<(ValidationCodeEnum, Option<String>)> {
+            let errors = extract.issues.iter().filter(|i| i.issue.is_error());
+            errors.map(|i| (i.issue.code, i.column.clone())).collect()
+        };
+
+        let df = p001_with(&[
+            ("fund", "F001"),
+            ("fund_tp_pct", "60"),
+            ("fund_ep_pct", "100"),
+            ("fund_2", "F002"),
+            ("fund_tp_pct_2", "30"),
+            ("fund_ep_pct_2", "0"),
+            ("withdrawal_from", "5"),
+            ("withdrawal_to", "7"),
+            ("withdrawal_amount", "1000000"),
+            ("withdrawal_from_2", "6"),
+            ("withdrawal_to_2", "8"),
+            ("withdrawal_amount_2", "1000000"),
+        ]);
+        let extract = read_extract_df(&df, &ExtractMapping::default(), 2).unwrap();
+        assert!(extract.policies.is_empty());
+        let found = columns(&extract);
+        for expected in [
+            (ValidationCodeEnum::FundAllocTpSum, Some("fund".to_string())),
+            (
+                ValidationCodeEnum::WithdrawalOverlap,
+                Some("withdrawal_from_2".to_string()),
+            ),
+        ] {
+            assert!(
+                found.contains(&expected),
+                "{:?} not in {:?}",
+                expected,
+                found
+            );
+        }
+
+        // An element given in part, and rate points without their basis
+        let df = p001_with(&[
+            ("fund", "F001"),
+            ("fund_2", "F002"),
+            ("int_rate_low_from", "1"),
+            ("int_rate_low_rate", "0.01"),
+        ]);
+        let extract = read_extract_df(&df, &ExtractMapping::default(), 2).unwrap();
+        let found = columns(&extract);
+        for column in ["fund_tp_pct_2", "fund_ep_pct_2", "int_rate_basis"] {
+            let expected = (ValidationCodeEnum::MissingValue, Some(column.to_string()));
+            assert!(
+                found.contains(&expected),
+                "{:?} not in {:?}",
+                expected,
+                found
+            );
+        }
+    }
+}
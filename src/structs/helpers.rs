@@ -14,7 +14,35 @@ pub fn calculate_age<'a>(
     if (today.month(), today.day()) < (birthdate.month(), birthdate.day()) {
         age -= 1;
     }
-    Ok(age as i32)
+    Ok(age)
+}
+
+// Birthday in a given year; 29 February falls on 1 March in non-leap years, as in calculate_age
+fn _birthday_in(birthdate: &NaiveDate, year: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, birthdate.month(), birthdate.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, 3, 1))
+        .expect("1 March always exists")
+}
+
+/// Age on the given basis. Nearest birthday rounds up when the next birthday is
+/// no further away than the last one.
+pub fn calculate_age_on_basis(
+    birthdate: &NaiveDate,
+    today: &NaiveDate,
+    basis: AgeBasisEnum,
+) -> Result<i32, &'static str> {
+    let age = calculate_age(birthdate, today)?;
+    match basis {
+        AgeBasisEnum::LastBirthday => Ok(age),
+        AgeBasisEnum::NextBirthday => Ok(age + 1),
+        AgeBasisEnum::NearestBirthday => {
+            let last_birthday = _birthday_in(birthdate, birthdate.year() + age);
+            let next_birthday = _birthday_in(birthdate, birthdate.year() + age + 1);
+            let days_since = (*today - last_birthday).num_days();
+            let days_until = (next_birthday - *today).num_days();
+            Ok(age + (days_until <= days_since) as i32)
+        }
+    }
 }
 
 pub fn calculate_month_age(birthdate: &NaiveDate, today: &NaiveDate) -> Result<i32, &'static str> {
@@ -30,6 +58,20 @@ pub fn calculate_month_age(birthdate: &NaiveDate, today: &NaiveDate) -> Result<i
     Ok(total_months)
 }
 
+/// Converts a garde error path into a JSON pointer (RFC 6901), e.g. `/base/withdrawal_plan/1/from`.
+pub fn json_pointer(path: &garde::Path) -> String {
+    // Path only exposes its components through serde: [["key", "base"], ["index", "1"], ...]
+    let components = serde_json::to_value(path).unwrap_or_default();
+    components
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|c| c[0] != "none")
+        .filter_map(|c| c[1].as_str())
+        .map(|c| format!("/{}", c.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
 //-----------------------------------------------------------------------------
 // UNIT TESTS
 // -----------------------------------------------------------------------------
@@ -99,6 +141,50 @@ mod tests {
         assert_eq!(result, Err("Input date cannot be less than birthdate."));
     }
 
+    // Test fn calculate_age_on_basis
+    fn ages(dob: (i32, u32, u32), today: (i32, u32, u32)) -> (i32, i32, i32) {
+        let birthdate = NaiveDate::from_ymd_opt(dob.0, dob.1, dob.2).unwrap();
+        let todate = NaiveDate::from_ymd_opt(today.0, today.1, today.2).unwrap();
+        let age = |basis| calculate_age_on_basis(&birthdate, &todate, basis).unwrap();
+        (
+            age(AgeBasisEnum::LastBirthday),
+            age(AgeBasisEnum::NearestBirthday),
+            age(AgeBasisEnum::NextBirthday),
+        )
+    }
+
+    #[test]
+    fn test_fn_calculate_age_on_basis_01() {
+        // Born 29 Feb: in a non-leap year the birthday falls on 1 March
+        assert_eq!(ages((2000, 2, 29), (2025, 2, 28)), (24, 25, 25));
+        assert_eq!(ages((2000, 2, 29), (2025, 3, 1)), (25, 25, 26));
+        // and on the day itself in a leap year
+        assert_eq!(ages((2000, 2, 29), (2024, 2, 28)), (23, 24, 24));
+        assert_eq!(ages((2000, 2, 29), (2024, 2, 29)), (24, 24, 25));
+    }
+
+    #[test]
+    fn test_fn_calculate_age_on_basis_02() {
+        // Half-way between 29 Feb 2024 and 1 Mar 2025: 184 days since, 182 days to go on 31 Aug
+        assert_eq!(ages((2000, 2, 29), (2024, 8, 29)), (24, 24, 25));
+        assert_eq!(ages((2000, 2, 29), (2024, 8, 30)), (24, 25, 25));
+        assert_eq!(ages((2000, 2, 29), (2024, 8, 31)), (24, 25, 25));
+    }
+
+    #[test]
+    fn test_fn_calculate_age_on_basis_03() {
+        // Newborn on the day of birth
+        assert_eq!(ages((2025, 2, 23), (2025, 2, 23)), (0, 0, 1));
+        assert!(
+            calculate_age_on_basis(
+                &NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+                &NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                AgeBasisEnum::NearestBirthday
+            )
+            .is_err()
+        );
+    }
+
     // Test fn calculate_month_age
 
     #[test]
@@ -178,4 +264,20 @@ mod tests {
         let result = calculate_month_age(&birthdate, &todate);
         assert_eq!(result, Err("Input date cannot be less than birthdate."));
     }
+
+    // Test fn json_pointer
+    #[test]
+    fn test_fn_json_pointer_01() {
+        let path = garde::Path::new("base")
+            .join("withdrawal_plan")
+            .join(1_usize)
+            .join("from");
+        assert_eq!(json_pointer(&path), "/base/withdrawal_plan/1/from");
+    }
+
+    #[test]
+    fn test_fn_json_pointer_02() {
+        assert_eq!(json_pointer(&garde::Path::empty()), "");
+        assert_eq!(json_pointer(&garde::Path::new("a/b~c")), "/a~1b~0c");
+    }
 }
@@ -0,0 +1,114 @@
+use super::*;
+use crate::structs::helpers::calculate_age;
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+pub struct PastWithdrawal {
+    #[garde(range(min = 1))]
+    pub year: i32, // policy year
+
+    #[garde(range(min = 0.0))]
+    pub tav_withdrawal: f64,
+}
+
+// Values of an in-force policy on a policy anniversary, before the premium of that year.
+// Bonuses already credited are part of the TAV.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[garde(allow_unvalidated)]
+pub struct Inforce {
+    pub valuation_date: NaiveDate,
+
+    #[garde(range(min = 0.0))]
+    pub si: f64,
+
+    #[garde(range(min = 0.0))]
+    pub eav: f64,
+
+    #[garde(range(min = 0.0))]
+    pub tav: f64,
+
+    // TAV withdrawals of past years, looked back over by the bonus reviews
+    #[serde(default)]
+    #[garde(dive)]
+    pub withdrawal_history: Vec<PastWithdrawal>,
+}
+
+impl Inforce {
+    // Policy year starting on the valuation date
+    pub fn first_year(&self, rcd: &NaiveDate) -> PolarsResult<i32> {
+        let years = calculate_age(rcd, &self.valuation_date)
+            .map_err(|_| PolarsError::ComputeError("Valuation date is before the RCD".into()))?;
+        let on_anniversary = self.valuation_date == *rcd
+            || self
+                .valuation_date
+                .pred_opt()
+                .and_then(|day_before| calculate_age(rcd, &day_before).ok())
+                .is_some_and(|years_before| years_before < years);
+        if !on_anniversary {
+            let err_msg = format!(
+                "Valuation date {} is not a policy anniversary",
+                self.valuation_date
+            );
+            return Err(PolarsError::ComputeError(err_msg.into()));
+        }
+        Ok(years + 1)
+    }
+
+    // TAV withdrawn in each policy year before first_year; years not in the history withdrew nothing
+    pub fn tav_withdrawals(&self, first_year: i32) -> Vec<f64> {
+        let mut withdrawals = vec![0.0; (first_year - 1).max(0) as usize];
+        for past in &self.withdrawal_history {
+            if (1..first_year).contains(&past.year) {
+                withdrawals[(past.year - 1) as usize] += past.tav_withdrawal;
+            }
+        }
+        withdrawals
+    }
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inforce(valuation_date: &str) -> Inforce {
+        Inforce {
+            valuation_date: NaiveDate::parse_from_str(valuation_date, "%Y-%m-%d").unwrap(),
+            si: 100_000_000.0,
+            eav: 0.0,
+            tav: 0.0,
+            withdrawal_history: vec![
+                PastWithdrawal {
+                    year: 2,
+                    tav_withdrawal: 1_000_000.0,
+                },
+                PastWithdrawal {
+                    year: 5,
+                    tav_withdrawal: 2_000_000.0,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_struct_inforce_01() {
+        let rcd = NaiveDate::from_ymd_opt(2020, 2, 29).unwrap();
+        assert_eq!(inforce("2020-02-29").first_year(&rcd).unwrap(), 1);
+        // An RCD on 29 February has its anniversary on 1 March in non-leap years
+        assert_eq!(inforce("2021-03-01").first_year(&rcd).unwrap(), 2);
+        assert_eq!(inforce("2024-02-29").first_year(&rcd).unwrap(), 5);
+        assert!(inforce("2021-02-28").first_year(&rcd).is_err());
+        assert!(inforce("2019-02-28").first_year(&rcd).is_err());
+    }
+
+    #[test]
+    fn test_struct_inforce_02() {
+        // Only the years before the first projected year are kept
+        let withdrawals = inforce("2024-02-29").tav_withdrawals(4);
+        assert_eq!(withdrawals, vec![0.0, 1_000_000.0, 0.0]);
+    }
+}
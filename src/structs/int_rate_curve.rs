@@ -0,0 +1,161 @@
+use super::*;
+use crate::structs::validation::coded_error;
+use chrono::Months;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn optional_points_validation(value: &Option<Vec<IntRatePoint>>, ctx: &()) -> garde::Result {
+    match value {
+        Some(points) => points_validation(points, ctx),
+        None => Ok(()),
+    }
+}
+
+// Calendar-year rates as policy-year points. Each policy year takes the average rate of its
+// 12 months, each month at the rate of the calendar year it starts in, so a policy year spanning
+// two calendar rates is credited in proportion to the months under each.
+fn _calendar_to_policy_points(points: &[IntRatePoint], rcd: &NaiveDate) -> Vec<(i32, f64)> {
+    // Index of the point in force in a calendar year; earlier years take the first point
+    let in_force = |cal_year: i32| points.iter().rposition(|p| p.from <= cal_year).unwrap_or(0);
+    // Policy years from the one starting in the calendar year of the last point take its rate
+    let last_policy_year = points.last().map_or(1, |p| p.from - rcd.year() + 1).max(1);
+
+    let mut result: Vec<(i32, f64)> = Vec::new();
+    for policy_year in 1..=last_policy_year {
+        let mut months = vec![0; points.len()];
+        for month in 0..12 {
+            let start = *rcd + Months::new(((policy_year - 1) * 12 + month) as u32);
+            months[in_force(start.year())] += 1;
+        }
+        let rate = match months.iter().position(|m| *m == 12) {
+            Some(i) => points[i].rate,
+            None => {
+                let weighted: f64 = (points.iter().zip(&months))
+                    .map(|(p, m)| p.rate * *m as f64)
+                    .sum();
+                weighted / 12.0
+            }
+        };
+        if result.last().is_none_or(|(_, last)| *last != rate) {
+            result.push((policy_year, rate));
+        }
+    }
+    result
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// Also validates the crediting rates of custom scenarios
+pub(crate) fn points_validation(points: &[IntRatePoint], _ctx: &()) -> garde::Result {
+    // A curve given for a scenario needs at least one point
+    if points.is_empty() {
+        return Err(coded_error(
+            ValidationCodeEnum::IntRateCurveEmpty,
+            "Interest rate curve cannot be empty",
+        ));
+    }
+
+    // Each point must start after the previous one
+    if points.windows(2).any(|w| w[1].from <= w[0].from) {
+        return Err(coded_error(
+            ValidationCodeEnum::IntRateCurveOrder,
+            "Interest rate points must be in increasing 'from' year order",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[garde(allow_unvalidated)]
+pub struct IntRatePoint {
+    pub from: i32, // policy or calendar year, per the curve basis
+
+    #[garde(range(min = -1.0, max = 1.0))]
+    pub rate: f64,
+}
+
+// Per-policy crediting rates, overriding the rate table for the scenarios given.
+// Each rate applies from its year until the next point; the first rate also covers earlier years.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[garde(allow_unvalidated)]
+pub struct IntRateCurve {
+    pub basis: YearBasisEnum,
+
+    #[garde(custom(optional_points_validation))]
+    #[garde(dive)]
+    pub high: Option<Vec<IntRatePoint>>,
+
+    #[garde(custom(optional_points_validation))]
+    #[garde(dive)]
+    pub low: Option<Vec<IntRatePoint>>,
+
+    #[garde(custom(optional_points_validation))]
+    #[garde(dive)]
+    pub guaranteed: Option<Vec<IntRatePoint>>,
+}
+
+impl IntRateCurve {
+    // Points of the scenario as (from policy year, rate), or None when the table applies
+    pub fn points(
+        &self,
+        scenario: IntRateScenarioEnum,
+        rcd: &NaiveDate,
+    ) -> Option<Vec<(i32, f64)>> {
+        let points = match scenario {
+            IntRateScenarioEnum::High => self.high.as_ref(),
+            IntRateScenarioEnum::Low => self.low.as_ref(),
+            IntRateScenarioEnum::Guaranteed => self.guaranteed.as_ref(),
+        }?;
+
+        match self.basis {
+            YearBasisEnum::PolicyYear => Some(points.iter().map(|p| (p.from, p.rate)).collect()),
+            YearBasisEnum::CalendarYear => Some(_calendar_to_policy_points(points, rcd)),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::from_str;
+
+    #[test]
+    fn test_struct_int_rate_curve_01() {
+        let json = r#"{
+            "basis": "CalendarYear",
+            "high": [{"from": 2025, "rate": 0.08}, {"from": 2028, "rate": 0.06}]
+        }"#;
+        let curve: IntRateCurve = from_str(json).unwrap();
+        assert!(curve.validate().is_ok());
+
+        // Policy years start on 1 January: each takes the rate of its calendar year
+        let rcd = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let points = curve.points(IntRateScenarioEnum::High, &rcd).unwrap();
+        assert_eq!(points, vec![(1, 0.08), (4, 0.06)]);
+        assert!(curve.points(IntRateScenarioEnum::Low, &rcd).is_none());
+
+        // Policy year 3 from 23 February 2027 has 11 months at 8% and January 2028 at 6%
+        let rcd = NaiveDate::from_ymd_opt(2025, 2, 23).unwrap();
+        let points = curve.points(IntRateScenarioEnum::High, &rcd).unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!((points[0], points[2]), ((1, 0.08), (4, 0.06)));
+        assert_eq!(points[1].0, 3);
+        approx::assert_abs_diff_eq!(points[1].1, (11.0 * 0.08 + 0.06) / 12.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_struct_int_rate_curve_02() {
+        // Points out of order
+        let json = r#"{
+            "basis": "PolicyYear",
+            "low": [{"from": 5, "rate": 0.04}, {"from": 1, "rate": 0.05}]
+        }"#;
+        let curve: IntRateCurve = from_str(json).unwrap();
+        assert!(curve.validate().is_err());
+    }
+}
@@ -1,4 +1,7 @@
+use crate::enums::ValidationCodeEnum;
+use crate::structs::validation::coded_error;
 use garde::Validate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // -----------------------------------------------------------------------------
@@ -7,17 +10,21 @@ use serde::{Deserialize, Serialize};
 fn em_load_validation(value: &f64, _ctx: &()) -> garde::Result {
     // Check if the value is a multiple of 0.25
     if value % 0.25 != 0.0 {
-        return Err(garde::Error::new("Value is not a multiple of 0.25"));
+        return Err(coded_error(
+            ValidationCodeEnum::NotMultipleOfStep,
+            "Value is not a multiple of 0.25",
+        ));
     }
     Ok(())
 }
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 pub struct Load {
     #[garde(custom(em_load_validation))]
     #[garde(range(min = 0.0, max = 2.5))]
+    #[schemars(extend("multipleOf" = 0.25))]
     pub em_load: f64,
 
     #[garde(range(min = 0))]
@@ -48,7 +55,7 @@ mod tests {
         "pm_load": 2,
         "pm_load_term": 20
         }"#;
-        let load: Load = from_str(&json).unwrap();
+        let load: Load = from_str(json).unwrap();
         assert!(load.validate().is_ok());
         assert_eq!(load.em_load, 0.75);
     }
@@ -63,7 +70,7 @@ mod tests {
         "pm_load": 2,
         "pm_load_term": 20
         }"#;
-        let load: Load = from_str(&json).unwrap();
+        let load: Load = from_str(json).unwrap();
         assert!(load.validate().is_ok());
     }
 
@@ -77,7 +84,7 @@ mod tests {
         "pm_load": 2,
         "pm_load_term": 20
         }"#;
-        let load: Load = from_str(&json).unwrap();
+        let load: Load = from_str(json).unwrap();
         assert!(load.validate().is_ok());
     }
 
@@ -91,7 +98,7 @@ mod tests {
         "pm_load": 18,
         "pm_load_term": 20
         }"#;
-        let load: Load = from_str(&json).unwrap();
+        let load: Load = from_str(json).unwrap();
         assert!(load.validate().is_ok());
     }
 
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Owner {
     pub id: String,
@@ -11,7 +11,7 @@ pub struct Owner {
     pub dob: NaiveDate,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Agent {
     pub id: String,
@@ -24,7 +24,7 @@ pub struct Agent {
     pub gender: GenderEnum,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Beneficiary {
     pub id: String,
@@ -36,7 +36,7 @@ pub struct Beneficiary {
     pub relatsh: RelationshipToOwnerEnum,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Insured {
     pub id: String,
@@ -64,7 +64,7 @@ mod tests {
             "ssn": "123456789012",
             "dob": "1980-01-01"
         }"#;
-        let owner: Owner = from_str(&json).unwrap();
+        let owner: Owner = from_str(json).unwrap();
         assert!(owner.validate().is_ok());
     }
 
@@ -78,7 +78,7 @@ mod tests {
         "gender": "Female",
         "relatsh": "OwnerSelf"
         }"#;
-        let insured: Insured = serde_json::from_str(&json).unwrap();
+        let insured: Insured = serde_json::from_str(json).unwrap();
         assert!(insured.validate().is_ok());
     }
 }
@@ -1,41 +1,77 @@
 use super::*;
+use crate::database::product::ProductRules;
+use crate::structs::validation::{ValidationIssue, entry_age_issues, garde_issues};
 use crate::structs::{base::Base, people::Owner, rider::Rider};
+use serde_json::json;
 
 //-----------------------------------------------------------------------------
 // PRIVATE
 // -----------------------------------------------------------------------------
-fn base_validation(value: &Base, _ctx: &()) -> garde::Result {
-    _term_validation(value, _ctx)?;
-    _withdrawal_start_year_validation(value, _ctx)?;
-    _fund_alloc_default_validation(value, _ctx)?;
-    Ok(())
+fn _base_issues(value: &Base, rules: &ProductRules) -> Vec<ValidationIssue> {
+    let mut issues = _entry_age_issues(value, rules);
+    issues.extend(_term_issues(value, rules));
+    issues.extend(_amount_issues(value));
+    issues.extend(_withdrawal_start_year_issues(value));
+    issues.extend(_fund_alloc_default_issues(value));
+    issues.extend(_inforce_issues(value, rules));
+    issues
 }
 
-fn _term_validation(value: &Base, _ctx: &()) -> garde::Result {
-    // Map error to garde::Error
-    let term = match value.term() {
+fn _entry_age_issues(value: &Base, rules: &ProductRules) -> Vec<ValidationIssue> {
+    let pointer = "/base/insured/dob";
+    let entry_age = match value.entry_age(rules) {
+        Ok(age) => age,
+        Err(e) => return vec![ValidationIssue::lookup_failed(pointer, "entry age", e)],
+    };
+    // The base maturity age comes from the maturity option, not the table
+    let maturity_age = match value.maturity_age() {
+        Ok(age) => age,
+        Err(e) => return vec![ValidationIssue::lookup_failed(pointer, "maturity age", e)],
+    };
+    let entry_month_age = value.entry_month_age().ok();
+    entry_age_issues(&value.id, entry_age, entry_month_age, maturity_age, pointer)
+}
+
+fn _term_issues(value: &Base, rules: &ProductRules) -> Vec<ValidationIssue> {
+    use ValidationCodeEnum as Code;
+
+    let term = match value.term(rules) {
         Ok(t) => t,
         Err(e) => {
-            let err_msg = format!("Failed to get base/policy term: {}", e);
-            return Err(garde::Error::new(err_msg));
+            return vec![ValidationIssue::lookup_failed(
+                "/base",
+                "base/policy term",
+                e,
+            )];
         }
     };
 
     let must_pay_period = match value.must_pay_period() {
         Ok(mpp) => mpp,
         Err(e) => {
-            let err_msg = format!("Failed to get must pay period: {}", e);
-            return Err(garde::Error::new(err_msg));
+            return vec![ValidationIssue::lookup_failed(
+                "/base",
+                "must pay period",
+                e,
+            )];
         }
     };
 
+    let mut issues = Vec::new();
+
     // Opted tp term cannot exceed base/policy term
     if value.opted_tp_term > term {
         let err_msg = format!(
             "Opted term {} cannot be greater than base/policy term {}.",
             value.opted_tp_term, term
         );
-        return Err(garde::Error::new(err_msg));
+        let params = json!({ "opted_tp_term": value.opted_tp_term, "term": term });
+        issues.push(ValidationIssue::error(
+            Code::OptedTpTermAboveTerm,
+            "/base/opted_tp_term",
+            err_msg,
+            params,
+        ));
     }
 
     // opted_ep_term cannot exceed opted_ep_term
@@ -44,7 +80,16 @@ fn _term_validation(value: &Base, _ctx: &()) -> garde::Result {
             "Opted EP term {} cannot be greater than opted TP term {}.",
             value.opted_ep_term, value.opted_tp_term
         );
-        return Err(garde::Error::new(err_msg));
+        let params = json!({
+            "opted_ep_term": value.opted_ep_term,
+            "opted_tp_term": value.opted_tp_term
+        });
+        issues.push(ValidationIssue::error(
+            Code::OptedEpTermAboveTpTerm,
+            "/base/opted_ep_term",
+            err_msg,
+            params,
+        ));
     }
 
     // opted_ep_term must be at least must pay period
@@ -53,7 +98,16 @@ fn _term_validation(value: &Base, _ctx: &()) -> garde::Result {
             "Opted EP term {} cannot be less than must pay period {}.",
             value.opted_ep_term, must_pay_period
         );
-        return Err(garde::Error::new(err_msg));
+        let params = json!({
+            "opted_ep_term": value.opted_ep_term,
+            "must_pay_period": must_pay_period
+        });
+        issues.push(ValidationIssue::error(
+            Code::OptedEpTermBelowMustPayPeriod,
+            "/base/opted_ep_term",
+            err_msg,
+            params,
+        ));
     }
 
     // Load term validation - this is base on base input
@@ -65,7 +119,13 @@ fn _term_validation(value: &Base, _ctx: &()) -> garde::Result {
             "EM load term {} cannot be greater than base/policy term {}.",
             load.em_load_term, term
         );
-        return Err(garde::Error::new(err_msg));
+        let params = json!({ "em_load_term": load.em_load_term, "term": term });
+        issues.push(ValidationIssue::error(
+            Code::EmLoadTermAboveTerm,
+            "/base/load/em_load_term",
+            err_msg,
+            params,
+        ));
     }
 
     // Validate pm_load_term
@@ -74,51 +134,210 @@ fn _term_validation(value: &Base, _ctx: &()) -> garde::Result {
             "PM load term {} cannot be greater than base/policy term {}.",
             load.pm_load_term, term
         );
-        return Err(garde::Error::new(err_msg));
+        let params = json!({ "pm_load_term": load.pm_load_term, "term": term });
+        issues.push(ValidationIssue::error(
+            Code::PmLoadTermAboveTerm,
+            "/base/load/pm_load_term",
+            err_msg,
+            params,
+        ));
     }
 
-    Ok(())
+    issues
 }
 
-fn _withdrawal_start_year_validation(value: &Base, _ctx: &()) -> garde::Result {
+fn _amount_issues(value: &Base) -> Vec<ValidationIssue> {
+    use ValidationCodeEnum as Code;
+
+    let limit = match value.amount_limit() {
+        Ok(limit) => limit,
+        Err(e) => return vec![ValidationIssue::lookup_failed("/base", "amount limits", e)],
+    };
+    let product = value.id.as_ref();
+
+    let mut issues = Vec::new();
+    if value.si < limit.min_si || value.si > limit.max_si {
+        let err_msg = format!(
+            "SI {} of {} must be between {} and {}.",
+            value.si, product, limit.min_si, limit.max_si
+        );
+        let params =
+            json!({ "si": value.si, "min": limit.min_si, "max": limit.max_si, "product": product });
+        issues.push(ValidationIssue::error(
+            Code::SiOutOfRange,
+            "/base/si",
+            err_msg,
+            params,
+        ));
+    }
+
+    if value.ep < limit.min_ep || value.ep > limit.max_ep {
+        let err_msg = format!(
+            "EP {} of {} must be between {} and {}.",
+            value.ep, product, limit.min_ep, limit.max_ep
+        );
+        let params =
+            json!({ "ep": value.ep, "min": limit.min_ep, "max": limit.max_ep, "product": product });
+        issues.push(ValidationIssue::error(
+            Code::EpOutOfRange,
+            "/base/ep",
+            err_msg,
+            params,
+        ));
+    }
+    issues
+}
+
+fn _withdrawal_start_year_issues(value: &Base) -> Vec<ValidationIssue> {
     // Withdrawal start year validation - this is base on base input
-    if let Some(plan) = &value.withdrawal_plan {
-        if let Some(w) = plan.first() {
-            let start_year = value.withdrawal_start_year().unwrap_or(0);
-
-            if w.from < start_year {
-                let err_msg = format!(
-                    "Withdrawal must start from the {}. Current withdrawal starts from {}.",
-                    start_year, w.from
-                );
-                return Err(garde::Error::new(err_msg));
-            }
+    if let Some(w) = value.withdrawal_plan.as_ref().and_then(|plan| plan.first()) {
+        let start_year = value.withdrawal_start_year().unwrap_or(0);
+
+        if w.from < start_year {
+            let err_msg = format!(
+                "Withdrawal must start from the {}. Current withdrawal starts from {}.",
+                start_year, w.from
+            );
+            let params = json!({ "from": w.from, "start_year": start_year });
+            return vec![ValidationIssue::error(
+                ValidationCodeEnum::WithdrawalBeforeStartYear,
+                "/base/withdrawal_plan/0/from",
+                err_msg,
+                params,
+            )];
         }
     }
-    Ok(())
+    Vec::new()
 }
 
-fn _fund_alloc_default_validation(value: &Base, _ctx: &()) -> garde::Result {
+fn _fund_alloc_default_issues(value: &Base) -> Vec<ValidationIssue> {
     // Fund allocation validation - this is base on base input
     let fa = &value.fund_alloc;
     match value.id {
         ULEnum::UVL01 | ULEnum::UVL02 | ULEnum::UVL03 => {
             let err_msg = "Fund allocation must contain exactly one entry: \"F000\".";
             if fa.len() != 1 || fa[0].fund != FundEnum::F000 {
-                return Err(garde::Error::new(err_msg));
+                return vec![ValidationIssue::error(
+                    ValidationCodeEnum::FundAllocDefault,
+                    "/base/fund_alloc",
+                    err_msg.to_string(),
+                    json!({ "fund": FundEnum::F000.as_ref() }),
+                )];
             }
         }
         _ => {
             // For other plans, fund allocation can be empty or contain multiple entries
         }
     }
-    Ok(())
+    Vec::new()
+}
+
+fn _inforce_issues(value: &Base, rules: &ProductRules) -> Vec<ValidationIssue> {
+    use ValidationCodeEnum as Code;
+
+    let Some(inforce) = &value.inforce else {
+        return Vec::new();
+    };
+    let pointer = "/base/inforce/valuation_date";
+
+    // The values are as at the start of a policy year
+    let first_year = match inforce.first_year(&value.rcd) {
+        Ok(year) => year,
+        Err(_) => {
+            let err_msg = format!(
+                "Valuation date {} must be a policy anniversary on or after the RCD {}.",
+                inforce.valuation_date, value.rcd
+            );
+            let params = json!({ "valuation_date": inforce.valuation_date, "rcd": value.rcd });
+            return vec![ValidationIssue::error(
+                Code::ValuationDateNotAnniversary,
+                pointer,
+                err_msg,
+                params,
+            )];
+        }
+    };
+    let term = match value.term(rules) {
+        Ok(t) => t,
+        Err(e) => {
+            return vec![ValidationIssue::lookup_failed(
+                pointer,
+                "base/policy term",
+                e,
+            )];
+        }
+    };
+
+    let mut issues = Vec::new();
+    if first_year > term {
+        let err_msg = format!(
+            "Valuation date {} starts policy year {}, beyond the base/policy term {}.",
+            inforce.valuation_date, first_year, term
+        );
+        let params = json!({ "first_year": first_year, "term": term });
+        issues.push(ValidationIssue::error(
+            Code::ValuationDateBeyondTerm,
+            pointer,
+            err_msg,
+            params,
+        ));
+    }
+
+    for (i, past) in inforce.withdrawal_history.iter().enumerate() {
+        if past.year >= first_year {
+            let err_msg = format!(
+                "Withdrawal history year {} is not before the first projected year {}.",
+                past.year, first_year
+            );
+            let params = json!({ "year": past.year, "first_year": first_year });
+            issues.push(ValidationIssue::error(
+                Code::WithdrawalHistoryNotBeforeValuation,
+                &format!("/base/inforce/withdrawal_history/{}/year", i),
+                err_msg,
+                params,
+            ));
+        }
+    }
+    issues
+}
+
+fn _duplicate_rider_issues(riders: &[Rider]) -> Vec<ValidationIssue> {
+    // The same rider cannot cover the same insured twice
+    let mut issues = Vec::new();
+    for (i, rider) in riders.iter().enumerate() {
+        if let Some(j) = riders[..i]
+            .iter()
+            .position(|r| r.id == rider.id && r.insured.id == rider.insured.id)
+        {
+            let err_msg = format!(
+                "Rider {} duplicates rider {}: {} on insured {}.",
+                i,
+                j,
+                rider.id.as_ref(),
+                rider.insured.id
+            );
+            let params = json!({
+                "index": i,
+                "duplicate_of": j,
+                "rider": rider.id.as_ref(),
+                "insured": rider.insured.id
+            });
+            issues.push(ValidationIssue::error(
+                ValidationCodeEnum::DuplicateRider,
+                &format!("/rider/{}", i),
+                err_msg,
+                params,
+            ));
+        }
+    }
+    issues
 }
 
 //-----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+// garde covers the fields on their own; rules across fields and rate tables are in validation_issues
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Policy {
     pub id: String,
@@ -128,7 +347,6 @@ pub struct Policy {
 
     pub created_date: NaiveDate,
 
-    #[garde(custom(base_validation))]
     #[garde(dive)]
     pub base: Base,
 
@@ -136,6 +354,50 @@ pub struct Policy {
     pub rider: Option<Vec<Rider>>,
 }
 
+impl Policy {
+    /// JSON Schema of the policy input, including the garde ranges.
+    /// The published copy lives in schema/policy.schema.json.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(Policy)).expect("Schema is always valid JSON")
+    }
+
+    /// Every issue of the policy, errors and warnings, across base, riders and people.
+    /// Each carries a stable code, the JSON pointer of the offending field and the values quoted in its message.
+    pub fn validation_issues(&self) -> Vec<ValidationIssue> {
+        let mut issues = match self.validate() {
+            Ok(()) => Vec::new(),
+            Err(report) => garde_issues(&report),
+        };
+        let riders = self.rider.as_deref().unwrap_or_default();
+
+        // The rules against the rate tables need the age basis of the product
+        match self.base.rules() {
+            Ok(rules) => {
+                issues.extend(_base_issues(&self.base, &rules));
+                for (i, rider) in riders.iter().enumerate() {
+                    issues.extend(rider.validation_issues(i, &self.base, &rules));
+                }
+            }
+            Err(e) => issues.push(ValidationIssue::lookup_failed(
+                "/base/id",
+                "product rules",
+                e,
+            )),
+        }
+        issues.extend(_duplicate_rider_issues(riders));
+        issues
+    }
+
+    /// Validates the policy. Returns the warnings when there is no error, else every issue.
+    pub fn validate_fields(&self) -> Result<Vec<ValidationIssue>, Vec<ValidationIssue>> {
+        let issues = self.validation_issues();
+        match issues.iter().any(|issue| issue.is_error()) {
+            true => Err(issues),
+            false => Ok(issues),
+        }
+    }
+}
+
 //-----------------------------------------------------------------------------
 // UNIT TESTS
 // -----------------------------------------------------------------------------
@@ -144,12 +406,282 @@ mod tests {
     use super::*;
     use crate::helpers::read_json_struct;
     use crate::structs::policy::Policy;
+    use crate::structs::validation::ValidationIssue;
 
     #[test]
     fn test_struct_policy_validation_01() {
         // Load a valid policy from JSON and validate it
-        let path = "src/models/test_data/uvl01_policy.json";
+        let path = "src/structs/test_data/uvl01_policy.json";
         let policy: Policy = read_json_struct(path).expect("Failed to read policy JSON");
         policy.validate().expect("Policy validation failed");
+
+        // UVL01 and PPD01 have no minimum entry age and no rider has SI limits, which are only warnings
+        let warnings = policy.validate_fields().expect("Policy validation failed");
+        let codes: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.pointer.as_str(), w.code))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                ("/base/insured/dob", ValidationCodeEnum::ManualReview),
+                ("/rider/0/insured/dob", ValidationCodeEnum::ManualReview),
+                ("/rider/0/si", ValidationCodeEnum::ManualReview),
+                ("/rider/1/si", ValidationCodeEnum::ManualReview),
+                ("/rider/2/si", ValidationCodeEnum::ManualReview),
+                ("/rider/5/si", ValidationCodeEnum::ManualReview),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct_policy_validation_02() {
+        // Overlapping withdrawal rows and an out-of-range load are reported at their own fields
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["base"]["load"]["em_load"] = 3.0.into();
+        json["base"]["withdrawal_plan"] = serde_json::json!([
+            {"from": 10, "to": 12, "amount": 1000.0},
+            {"from": 12, "to": 15, "amount": 1000.0}
+        ]);
+        let policy: Policy = serde_json::from_value(json).unwrap();
+
+        let errors = policy.validate_fields().unwrap_err();
+        let pointers: Vec<&str> = errors.iter().map(|e| e.pointer.as_str()).collect();
+        assert!(pointers.contains(&"/base/load/em_load"), "{:?}", pointers);
+        assert!(
+            pointers.contains(&"/base/withdrawal_plan/1/from"),
+            "{:?}",
+            pointers
+        );
+    }
+
+    #[test]
+    fn test_struct_policy_amount_validation_01() {
+        // SI below the product minimum and EP above its maximum
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["base"]["si"] = 50_000_000.0.into();
+        json["base"]["ep"] = 200_000_000_000.0.into();
+        json["rider"] = serde_json::Value::Null;
+        let policy: Policy = serde_json::from_value(json).unwrap();
+
+        let codes: Vec<_> = errors(&policy)
+            .iter()
+            .map(|e| (e.pointer.clone(), e.code))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                ("/base/si".to_string(), ValidationCodeEnum::SiOutOfRange),
+                ("/base/ep".to_string(), ValidationCodeEnum::EpOutOfRange),
+            ]
+        );
+    }
+
+    fn errors(policy: &Policy) -> Vec<ValidationIssue> {
+        let issues = policy.validation_issues();
+        issues.into_iter().filter(|i| i.is_error()).collect()
+    }
+
+    fn base_errors(dob: &str) -> Vec<ValidationIssue> {
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["base"]["insured"]["dob"] = dob.into();
+        json["rider"] = serde_json::Value::Null;
+        let policy: Policy = serde_json::from_value(json).unwrap();
+        errors(&policy)
+    }
+
+    #[test]
+    fn test_struct_policy_entry_age_validation_01() {
+        // RCD 2025-02-23: age 70 is above the maximum of 60
+        // The shorter term also leaves the opted TP term of 35 too long
+        let errors = base_errors("1955-01-01");
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(errors[0].pointer, "/base/insured/dob");
+        assert_eq!(errors[0].code, ValidationCodeEnum::EntryAgeAboveMaximum);
+        assert_eq!(errors[0].params["max"], 60);
+
+        // Age 60 is the last accepted
+        assert!(base_errors("1964-06-01").is_empty());
+    }
+
+    #[test]
+    fn test_struct_policy_entry_age_validation_02() {
+        // No minimum entry age is set, so newborns are referred to manual review
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["base"]["insured"]["dob"] = "2025-02-01".into();
+        json["rider"] = serde_json::Value::Null;
+        let policy: Policy = serde_json::from_value(json).unwrap();
+
+        let warnings = policy.validate_fields().unwrap();
+        let codes: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.pointer.as_str(), w.code))
+            .collect();
+        assert!(
+            codes.contains(&("/base/insured/dob", ValidationCodeEnum::ManualReview)),
+            "{:?}",
+            codes
+        );
+    }
+
+    #[test]
+    fn test_struct_policy_inforce_validation_01() {
+        // In-force values are as at a policy anniversary, with the history before it
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["rider"] = serde_json::Value::Null;
+        json["base"]["inforce"] = serde_json::json!({
+            "valuation_date": "2030-02-23", "si": 101000000, "eav": 0, "tav": 25000000,
+            "withdrawal_history": [{"year": 5, "tav_withdrawal": 0}, {"year": 6, "tav_withdrawal": 0}]
+        });
+        let policy: Policy = serde_json::from_value(json.clone()).unwrap();
+        let issues = errors(&policy);
+        assert_eq!(
+            pointers(&issues),
+            vec!["/base/inforce/withdrawal_history/1/year"]
+        );
+        assert_eq!(issues[0].params["first_year"], 6);
+
+        json["base"]["inforce"]["valuation_date"] = "2030-03-01".into();
+        let policy: Policy = serde_json::from_value(json).unwrap();
+        let issues = errors(&policy);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(
+            issues[0].code,
+            ValidationCodeEnum::ValuationDateNotAnniversary
+        );
+    }
+
+    fn rider_errors(json: serde_json::Value) -> Vec<ValidationIssue> {
+        let policy: Policy = serde_json::from_value(json).unwrap();
+        errors(&policy)
+    }
+
+    fn pointers(errors: &[ValidationIssue]) -> Vec<&str> {
+        errors.iter().map(|e| e.pointer.as_str()).collect()
+    }
+
+    #[test]
+    fn test_struct_policy_rider_validation_01() {
+        // Age, SI and HOP02 option failures are reported at the failing rider
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["rider"][1]["insured"]["dob"] = "2010-01-01".into(); // ADD01 from 18
+        json["rider"][2]["si"] = serde_json::Value::Null; // CIR02 needs an SI
+        json["rider"][3]["hop2_option"] = 1.into(); // SUP01 has no plans
+        json["rider"][4]["hop2_option"] = serde_json::Value::Null; // HOP02 needs a plan
+        json["rider"][5]["si"] = 0.0.into(); // CIR01 needs a positive SI
+
+        let errors = rider_errors(json);
+        let pointers = pointers(&errors);
+        for pointer in [
+            "/rider/1/insured/dob",
+            "/rider/2/si",
+            "/rider/3/hop2_option",
+            "/rider/4/hop2_option",
+            "/rider/5/si",
+        ] {
+            assert!(pointers.contains(&pointer), "{:?}", pointers);
+        }
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_struct_policy_rider_validation_02() {
+        // Riders must follow the base RCD and paymode
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["rider"][0]["rcd"] = "2025-03-01".into();
+        json["rider"][1]["paymode"] = "Monthly".into();
+
+        let errors = rider_errors(json);
+        assert_eq!(pointers(&errors), vec!["/rider/0/rcd", "/rider/1/paymode"]);
+    }
+
+    #[test]
+    fn test_struct_policy_rider_validation_03() {
+        // The same rider on the same insured twice
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        let add01 = json["rider"][1].clone();
+        json["rider"].as_array_mut().unwrap().push(add01);
+
+        let errors = rider_errors(json);
+        assert_eq!(pointers(&errors), vec!["/rider/6"]);
+        assert_eq!(errors[0].code, ValidationCodeEnum::DuplicateRider);
+        assert_eq!(errors[0].params["duplicate_of"], 1);
+    }
+
+    #[test]
+    fn test_struct_policy_validation_issues_01() {
+        // Every failure is reported at once, across people, base and riders
+        let path = "src/structs/test_data/uvl01_policy.json";
+        let mut json: serde_json::Value = read_json_struct(path).unwrap();
+        json["owner"]["ssn"] = "123".into();
+        json["base"]["load"]["em_load"] = 3.0.into();
+        json["base"]["insured"]["dob"] = "1955-01-01".into();
+        json["base"]["opted_ep_term"] = 200.into();
+        json["rider"][0]["paymode"] = "Monthly".into();
+        let policy: Policy = serde_json::from_value(json).unwrap();
+
+        let issues = policy.validate_fields().unwrap_err();
+        let found: Vec<_> = issues
+            .iter()
+            .filter(|i| i.is_error())
+            .map(|i| (i.pointer.as_str(), i.code))
+            .collect();
+        for expected in [
+            ("/owner/ssn", ValidationCodeEnum::LengthBelowMinimum),
+            ("/base/load/em_load", ValidationCodeEnum::AboveMaximum),
+            (
+                "/base/insured/dob",
+                ValidationCodeEnum::EntryAgeAboveMaximum,
+            ),
+            (
+                "/base/opted_ep_term",
+                ValidationCodeEnum::OptedEpTermAboveTpTerm,
+            ),
+            ("/rider/0/paymode", ValidationCodeEnum::PaymodeMismatch),
+        ] {
+            assert!(
+                found.contains(&expected),
+                "{:?} not in {:?}",
+                expected,
+                found
+            );
+        }
+
+        // Issues serialize with their code, severity and parameters for front-ends
+        let ssn_issue = issues.iter().find(|i| i.pointer == "/owner/ssn").unwrap();
+        let json = serde_json::to_value(ssn_issue).unwrap();
+        assert_eq!(json["code"], "LengthBelowMinimum");
+        assert_eq!(json["severity"], "Error");
+        assert_eq!(json["params"]["min"], 12.0);
+    }
+
+    #[test]
+    fn test_struct_policy_json_schema_01() {
+        // Regenerate the published schema with: UPDATE_SCHEMA=1 cargo test test_struct_policy_json_schema_01
+        let path = "schema/policy.schema.json";
+        let schema = Policy::json_schema();
+        if std::env::var("UPDATE_SCHEMA").is_ok() {
+            std::fs::write(path, serde_json::to_string_pretty(&schema).unwrap() + "\n").unwrap();
+        }
+        let published: serde_json::Value =
+            read_json_struct(path).expect("Missing published schema");
+        assert_eq!(
+            schema, published,
+            "schema/policy.schema.json is out of date"
+        );
+
+        let load = &schema["$defs"]["Load"]["properties"];
+        assert_eq!(load["em_load"]["maximum"], 2.5);
+        let base = &schema["$defs"]["Base"]["properties"];
+        assert_eq!(base["acc_ben_coeff"]["minimum"], 1);
+        assert_eq!(base["acc_ben_coeff"]["maximum"], 5);
     }
 }
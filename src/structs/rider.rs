@@ -1,8 +1,40 @@
 use super::*;
+use crate::database::{
+    age_validation::get_maturity_age,
+    modal_factor::get_modal_factor_tuple,
+    product::{ProductRules, get_age_basis, get_currency, get_product_rules},
+    rider_death_ben::get_rider_death_ben_pct,
+    rider_prem_rate::{get_rider_options, get_rider_prem_rate},
+    rider_si_limit::get_rider_si_limit,
+};
 use crate::enums::RiderEnum;
-use crate::structs::{load::Load, people::Insured};
+use crate::structs::validation::{ValidationIssue, entry_age_issues};
+use crate::structs::{
+    base::Base,
+    helpers::{calculate_age_on_basis, calculate_month_age},
+    load::Load,
+    people::Insured,
+};
+use serde_json::json;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// SUP01 tariff runs out after 17 years of cover (Rider_CF term of the workbook)
+const SUP01_MAX_TERM: i32 = 17;
+
+// WOP02 discount factor of Tariff!AD:AE for k years left after the current one:
+// f(0) = 0.5 * 1.035^-0.5 and f(k) = f(k - 1) + 1.035^-(0.5 + k)
+fn wop2_discount_factor(k: i32) -> f64 {
+    let v = 1.0 / 1.035_f64;
+    (1..=k).fold(0.5 * v.powf(0.5), |f, j| f + v.powf(0.5 + j as f64))
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+// garde covers the fields on their own; rules against the rate tables and the base are in validation_issues
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Rider {
     pub id: RiderEnum,
@@ -10,14 +42,556 @@ pub struct Rider {
     pub paymode: PayModeEnum,
     pub channel: ChannelEnum,
     pub status: StatusEnum,
+
+    #[garde(dive)]
     pub insured: Insured,
+
+    #[garde(dive)]
     pub load: Option<Load>,
+
     pub si: Option<f64>,
     pub hop2_option: Option<i32>,
 }
 
+impl Rider {
+    pub fn age_basis(&self) -> PolarsResult<AgeBasisEnum> {
+        get_age_basis(&self.id)
+    }
+
+    pub fn currency(&self) -> PolarsResult<CurrencyEnum> {
+        get_currency(&self.id)
+    }
+
+    // Loaded once per run, like the rules of the base
+    pub fn rules(&self) -> PolarsResult<ProductRules> {
+        get_product_rules(&self.id)
+    }
+
+    pub fn entry_age(&self, rules: &ProductRules) -> PolarsResult<i32> {
+        calculate_age_on_basis(&self.insured.dob, &self.rcd, rules.age_basis)
+            .map_err(|e| PolarsError::ComputeError(e.into()))
+    }
+
+    // Years of cover, to the rider maturity age in age_validation
+    pub fn term(&self, rules: &ProductRules) -> PolarsResult<i32> {
+        let term = get_maturity_age(&self.id)? - self.entry_age(rules)?;
+        match self.id {
+            RiderEnum::SUP01 => Ok(term.min(SUP01_MAX_TERM)),
+            _ => Ok(term),
+        }
+    }
+
+    // Lump sum paid on the death of the rider insured: the share of the rider SI in rider_death_ben
+    pub fn death_benefit(&self) -> PolarsResult<f64> {
+        Ok(get_rider_death_ben_pct(&self.id)? * self.si.unwrap_or(0.0))
+    }
+
+    // Riders rated by the annual premium of a plan rather than per 1000 SI
+    pub fn rated_by_plan(&self) -> bool {
+        matches!(self.id, RiderEnum::SUP01 | RiderEnum::HOP02)
+    }
+
+    // Plan of the rider in the rate table, 0 for riders without plans
+    pub fn option(&self) -> i32 {
+        match self.id {
+            RiderEnum::HOP02 => self.hop2_option.unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    // -------------------------------------------------
+    // The values that are set from database
+    // -------------------------------------------------
+    // Rate of the policy year: by attained age, except WOP01 by entry age and rider term
+    pub fn prem_rate(&self, year: i32, rules: &ProductRules) -> PolarsResult<f64> {
+        let entry_age = self.entry_age(rules)?;
+        let term = self.term(rules)?;
+        let (age, term) = match self.id {
+            RiderEnum::WOP01 => (entry_age, term),
+            RiderEnum::SUP01 => (entry_age + year - 1, term - year + 1),
+            _ => (entry_age + year - 1, 0),
+        };
+        get_rider_prem_rate(&self.id, &self.insured.gender, &age, &term, &self.option())
+    }
+
+    pub fn modal_factor_tuple(&self) -> PolarsResult<(f64, f64, f64, f64)> {
+        get_modal_factor_tuple(&self.id)
+    }
+
+    // Annual premium of the policy year before modal factors: standard premium plus the loads in force.
+    // The waivers spread the rate over the remaining term: WOP01 per year left, WOP02 discounted.
+    // EM load is a multiple of the standard premium, PM load is per 1000 SI.
+    fn crude_prem(&self, year: i32, rules: &ProductRules) -> PolarsResult<f64> {
+        let rate = self.prem_rate(year, rules)?;
+        let years_left = self.term(rules)? - year;
+        let factor = match self.id {
+            RiderEnum::WOP01 => (years_left + 1) as f64,
+            RiderEnum::WOP02 => wop2_discount_factor(years_left),
+            _ => 1.0,
+        };
+        let (std_prem, si) = match (self.rated_by_plan(), self.si) {
+            (true, _) => (rate, 0.0),
+            (false, Some(si)) => (rate * si / 1000.0 * factor, si),
+            (false, None) => {
+                let err_msg = format!("Rider {} requires an SI", self.id.as_ref());
+                return Err(PolarsError::ComputeError(err_msg.into()));
+            }
+        };
+
+        let (em_prem, pm_prem) = match &self.load {
+            Some(load) => (
+                (year <= load.em_load_term) as u8 as f64 * std_prem * load.em_load,
+                (year <= load.pm_load_term) as u8 as f64 * load.pm_load as f64 * si / 1000.0,
+            ),
+            None => (0.0, 0.0),
+        };
+
+        Ok(std_prem + em_prem + pm_prem)
+    }
+
+    // Premium per payment of the policy year, by mode: Annual, Semi-annual, Quarterly, Monthly
+    pub fn modal_prem_tuple(
+        &self,
+        year: i32,
+        rules: &ProductRules,
+    ) -> PolarsResult<(f64, f64, f64, f64)> {
+        let (f1, f2, f3, f4) = &self.modal_factor_tuple()?;
+        let crude_prem = self.crude_prem(year, rules)?;
+        let round = |x: f64| rules.rounding.round(RoundedQuantityEnum::ModalPrem, x);
+        let result = (
+            round(f1 * crude_prem)?,
+            round(f2 * crude_prem)?,
+            round(f3 * crude_prem)?,
+            round(f4 * crude_prem)?,
+        );
+        Ok(result)
+    }
+
+    // Premium per payment at the rider paymode
+    pub fn modal_prem(&self, year: i32, rules: &ProductRules) -> PolarsResult<f64> {
+        let (annual, semi_annual, quarterly, monthly) = self.modal_prem_tuple(year, rules)?;
+        let result = match self.paymode {
+            PayModeEnum::Annual => annual,
+            PayModeEnum::SemiAnnual => semi_annual,
+            PayModeEnum::Quarterly => quarterly,
+            PayModeEnum::Monthly => monthly,
+        };
+        Ok(result)
+    }
+
+    // Premium paid over the policy year at the rider paymode, nil once the cover ends
+    pub fn annual_prem(&self, year: i32, rules: &ProductRules) -> PolarsResult<f64> {
+        if year < 1 || year > self.term(rules)? {
+            return Ok(0.0);
+        }
+        let payments = match self.paymode {
+            PayModeEnum::Annual => 1.0,
+            PayModeEnum::SemiAnnual => 2.0,
+            PayModeEnum::Quarterly => 4.0,
+            PayModeEnum::Monthly => 12.0,
+        };
+        Ok(self.modal_prem(year, rules)? * payments)
+    }
+
+    // -------------------------------------------------
+    // Validation against the rate tables and the base
+    // -------------------------------------------------
+    /// Every issue of the rider at `index` of the policy rider list, given the rules of the base.
+    pub fn validation_issues(
+        &self,
+        index: usize,
+        base: &Base,
+        base_rules: &ProductRules,
+    ) -> Vec<ValidationIssue> {
+        let pointer = format!("/rider/{}", index);
+        let mut issues = match self.rules() {
+            Ok(rules) => {
+                let mut issues = self._entry_age_issues(&pointer, &rules);
+                issues.extend(self._term_issues(&pointer, &rules, base, base_rules));
+                issues
+            }
+            Err(e) => {
+                let pointer = format!("{}/id", pointer);
+                vec![ValidationIssue::lookup_failed(
+                    &pointer,
+                    "rider product rules",
+                    e,
+                )]
+            }
+        };
+        issues.extend(self._si_issues(&pointer));
+        issues.extend(self._hop2_option_issues(&pointer));
+        issues.extend(self._base_match_issues(&pointer, base));
+        issues
+    }
+
+    fn _entry_age_issues(&self, pointer: &str, rules: &ProductRules) -> Vec<ValidationIssue> {
+        let pointer = format!("{}/insured/dob", pointer);
+        let entry_age = match self.entry_age(rules) {
+            Ok(age) => age,
+            Err(e) => {
+                return vec![ValidationIssue::lookup_failed(
+                    &pointer,
+                    "rider entry age",
+                    e,
+                )];
+            }
+        };
+        let maturity_age = match get_maturity_age(&self.id) {
+            Ok(age) => age,
+            Err(e) => {
+                return vec![ValidationIssue::lookup_failed(
+                    &pointer,
+                    "rider maturity age",
+                    e,
+                )];
+            }
+        };
+        let entry_month_age = calculate_month_age(&self.insured.dob, &self.rcd).ok();
+        entry_age_issues(&self.id, entry_age, entry_month_age, maturity_age, &pointer)
+    }
+
+    fn _term_issues(
+        &self,
+        pointer: &str,
+        rules: &ProductRules,
+        base: &Base,
+        base_rules: &ProductRules,
+    ) -> Vec<ValidationIssue> {
+        use ValidationCodeEnum as Code;
+
+        // No term without a valid entry age, which is reported on its own
+        let (Ok(term), Ok(base_term)) = (self.term(rules), base.term(base_rules)) else {
+            return Vec::new();
+        };
+        let mut issues = Vec::new();
+
+        if let Some(load) = &self.load {
+            if load.em_load_term > term {
+                let err_msg = format!(
+                    "EM load term {} cannot be greater than rider term {}.",
+                    load.em_load_term, term
+                );
+                let params = json!({ "em_load_term": load.em_load_term, "term": term });
+                let pointer = format!("{}/load/em_load_term", pointer);
+                issues.push(ValidationIssue::error(
+                    Code::EmLoadTermAboveTerm,
+                    &pointer,
+                    err_msg,
+                    params,
+                ));
+            }
+
+            if load.pm_load_term > term {
+                let err_msg = format!(
+                    "PM load term {} cannot be greater than rider term {}.",
+                    load.pm_load_term, term
+                );
+                let params = json!({ "pm_load_term": load.pm_load_term, "term": term });
+                let pointer = format!("{}/load/pm_load_term", pointer);
+                issues.push(ValidationIssue::error(
+                    Code::PmLoadTermAboveTerm,
+                    &pointer,
+                    err_msg,
+                    params,
+                ));
+            }
+        }
+
+        // Cover ends with the base
+        if term > base_term {
+            let msg = format!(
+                "{} cover of {} years ends with the base after {} years.",
+                self.id.as_ref(),
+                term,
+                base_term
+            );
+            let params = json!({ "rider": self.id.as_ref(), "term": term, "base_term": base_term });
+            issues.push(ValidationIssue::warning(
+                Code::RiderCoverCappedByBase,
+                pointer,
+                msg,
+                params,
+            ));
+        }
+        issues
+    }
+
+    fn _si_issues(&self, pointer: &str) -> Vec<ValidationIssue> {
+        use ValidationCodeEnum as Code;
+
+        let pointer = format!("{}/si", pointer);
+        let limit = match get_rider_si_limit(&self.id) {
+            Ok(limit) => limit,
+            Err(e) => {
+                return vec![ValidationIssue::lookup_failed(
+                    &pointer,
+                    "rider SI limit",
+                    e,
+                )];
+            }
+        };
+        let name = self.id.as_ref();
+
+        match (limit, self.si) {
+            // Riders rated by plan carry no SI
+            (None, _) => Vec::new(),
+            (Some(_), None) => {
+                let err_msg = format!("SI is required for {}.", name);
+                let params = json!({ "rider": name });
+                vec![ValidationIssue::error(
+                    Code::SiRequired,
+                    &pointer,
+                    err_msg,
+                    params,
+                )]
+            }
+            (Some(_), Some(si)) if si <= 0.0 => {
+                let err_msg = format!("SI {} of {} must be positive.", si, name);
+                let params = json!({ "si": si, "min": 0, "rider": name });
+                vec![ValidationIssue::error(
+                    Code::SiOutOfRange,
+                    &pointer,
+                    err_msg,
+                    params,
+                )]
+            }
+            // A limit of -1 is not checked and left to manual review
+            (Some((min_si, max_si)), Some(_)) if min_si == -1.0 || max_si == -1.0 => {
+                let msg = format!("SI limits of {} are left to manual review.", name);
+                let params = json!({ "limit": "rider_si_limit", "product": name });
+                vec![ValidationIssue::warning(
+                    Code::ManualReview,
+                    &pointer,
+                    msg,
+                    params,
+                )]
+            }
+            (Some((min_si, max_si)), Some(si)) if si < min_si || si > max_si => {
+                let err_msg = format!(
+                    "SI {} of {} must be between {} and {}.",
+                    si, name, min_si, max_si
+                );
+                let params = json!({ "si": si, "min": min_si, "max": max_si, "rider": name });
+                vec![ValidationIssue::error(
+                    Code::SiOutOfRange,
+                    &pointer,
+                    err_msg,
+                    params,
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn _hop2_option_issues(&self, pointer: &str) -> Vec<ValidationIssue> {
+        use ValidationCodeEnum as Code;
+
+        let pointer = format!("{}/hop2_option", pointer);
+        let name = self.id.as_ref();
+
+        match (self.id, self.hop2_option) {
+            (RiderEnum::HOP02, None) => {
+                let err_msg = "HOP02 option is required for HOP02.".to_string();
+                let params = json!({ "rider": name });
+                vec![ValidationIssue::error(
+                    Code::Hop2OptionRequired,
+                    &pointer,
+                    err_msg,
+                    params,
+                )]
+            }
+            (RiderEnum::HOP02, Some(option)) => {
+                let options = match get_rider_options(&self.id) {
+                    Ok(options) => options,
+                    Err(e) => {
+                        return vec![ValidationIssue::lookup_failed(&pointer, "HOP02 options", e)];
+                    }
+                };
+                if options.contains(&option) {
+                    return Vec::new();
+                }
+                let err_msg = format!("HOP02 option {} must be one of {:?}.", option, options);
+                let params = json!({ "option": option, "options": options });
+                vec![ValidationIssue::error(
+                    Code::Hop2OptionInvalid,
+                    &pointer,
+                    err_msg,
+                    params,
+                )]
+            }
+            (_, Some(_)) => {
+                let err_msg = format!("HOP02 option is not applicable to {}.", name);
+                let params = json!({ "rider": name });
+                vec![ValidationIssue::error(
+                    Code::Hop2OptionNotApplicable,
+                    &pointer,
+                    err_msg,
+                    params,
+                )]
+            }
+            (_, None) => Vec::new(),
+        }
+    }
+
+    fn _base_match_issues(&self, pointer: &str, base: &Base) -> Vec<ValidationIssue> {
+        use ValidationCodeEnum as Code;
+
+        let mut issues = Vec::new();
+
+        if self.rcd != base.rcd {
+            let err_msg = format!("Rider RCD {} must match base RCD {}.", self.rcd, base.rcd);
+            let params = json!({ "rcd": self.rcd, "base_rcd": base.rcd });
+            let pointer = format!("{}/rcd", pointer);
+            issues.push(ValidationIssue::error(
+                Code::RcdMismatch,
+                &pointer,
+                err_msg,
+                params,
+            ));
+        }
+
+        if self.paymode != base.paymode {
+            let err_msg = format!(
+                "Rider paymode {} must match base paymode {}.",
+                self.paymode.as_ref(),
+                base.paymode.as_ref()
+            );
+            let params = json!({ "paymode": self.paymode, "base_paymode": base.paymode });
+            let pointer = format!("{}/paymode", pointer);
+            issues.push(ValidationIssue::error(
+                Code::PaymodeMismatch,
+                &pointer,
+                err_msg,
+                params,
+            ));
+        }
+        issues
+    }
+}
+
 // -----------------------------------------------------------------------------
 // UNIT TESTS
 // -----------------------------------------------------------------------------
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::structs::policy::Policy;
+    use strum::IntoEnumIterator;
+
+    // Riders of the sample policy, keyed by id
+    fn sample_rider(id: RiderEnum) -> Rider {
+        let json_path = "src/cashflows/test_data/uvl01_policy.json";
+        let policy = crate::helpers::read_json_struct::<Policy>(json_path).unwrap();
+        let template = policy.rider.unwrap().into_iter().next().unwrap();
+        let mut rider = Rider {
+            id,
+            insured: Insured {
+                dob: NaiveDate::from_ymd_opt(1993, 12, 25).unwrap(),
+                gender: GenderEnum::Female,
+                ..template.insured.clone()
+            },
+            load: None,
+            si: Some(100_000_000.0),
+            hop2_option: None,
+            ..template
+        };
+        if id == RiderEnum::HOP02 {
+            rider.si = None;
+            rider.hop2_option = Some(2);
+        }
+        rider
+    }
+
+    #[test]
+    fn test_struct_rider_prem_01() {
+        // Standard annual premium of the first year from the Tariff sheet of the workbook
+        // Female entry age 31, SI 100M, HOP02 plan 2
+        for id in RiderEnum::iter() {
+            let rider = sample_rider(id);
+            let rules = rider.rules().unwrap();
+            let result = rider.annual_prem(1, &rules).unwrap();
+            let expected = match id {
+                RiderEnum::ADD01 => 140_000.0,   // 1.4E-3 per 1 SI
+                RiderEnum::PPD01 => 118_000.0,   // 1.18E-3 per 1 SI up to age 60
+                RiderEnum::CIR01 => 227_000.0,   // 2.27
+                RiderEnum::CIR02 => 320_000.0,   // 3.2
+                RiderEnum::SUP01 => 355_000.0,   // 354,237 at age 31 with 17 years left
+                RiderEnum::HOP02 => 126_000.0,   // Plan 2
+                RiderEnum::WOP01 => 7_208_000.0, // 2.12 for term 34, over 34 years left
+                RiderEnum::WOP02 => 4_399_000.0, // 2.25 * f(33) of 19.551
+            };
+            assert_eq!(result, expected, "{}", id.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_struct_rider_prem_02() {
+        // Loads apply within their terms: 1.4 * 100M / 1000 * (1 + 0.75) + 2 * 100M / 1000
+        let mut rider = sample_rider(RiderEnum::ADD01);
+        rider.load = Some(Load {
+            em_load: 0.75,
+            em_load_term: 5,
+            pm_load: 2,
+            pm_load_term: 10,
+        });
+        let rules = rider.rules().unwrap();
+        assert_eq!(rider.annual_prem(5, &rules).unwrap(), 445_000.0);
+        assert_eq!(rider.annual_prem(6, &rules).unwrap(), 340_000.0);
+        assert_eq!(rider.annual_prem(11, &rules).unwrap(), 140_000.0);
+        // Nil once the cover ends at age 61
+        assert_eq!(rider.annual_prem(31, &rules).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_struct_rider_prem_03() {
+        // Modal factor of the rider: 12 payments of 0.09 * 118,000
+        let mut rider = sample_rider(RiderEnum::PPD01);
+        rider.paymode = PayModeEnum::Monthly;
+        let rules = rider.rules().unwrap();
+        assert_eq!(rider.modal_prem(1, &rules).unwrap(), 11_000.0);
+        assert_eq!(rider.annual_prem(1, &rules).unwrap(), 132_000.0);
+    }
+
+    #[test]
+    fn test_struct_rider_prem_04() {
+        // SI-rated riders need an SI
+        let mut rider = sample_rider(RiderEnum::CIR01);
+        rider.si = None;
+        let rules = rider.rules().unwrap();
+        assert!(rider.annual_prem(1, &rules).is_err());
+    }
+
+    #[test]
+    fn test_struct_rider_prem_05() {
+        // Rates follow the policy year: CIR01 at attained age 40 is 5.27
+        let rider = sample_rider(RiderEnum::CIR01);
+        let rules = rider.rules().unwrap();
+        assert_eq!(rider.annual_prem(10, &rules).unwrap(), 527_000.0);
+
+        // SUP01 covers 17 years, the last at age 47 with 1 year left for 82,126
+        let rider = sample_rider(RiderEnum::SUP01);
+        let rules = rider.rules().unwrap();
+        assert_eq!(rider.term(&rules).unwrap(), 17);
+        assert_eq!(rider.annual_prem(17, &rules).unwrap(), 83_000.0);
+        assert_eq!(rider.annual_prem(18, &rules).unwrap(), 0.0);
+
+        // WOP01 keeps the rate of its entry age and term over fewer years left: 2.12 * 33
+        let rider = sample_rider(RiderEnum::WOP01);
+        let rules = rider.rules().unwrap();
+        assert_eq!(rider.annual_prem(2, &rules).unwrap(), 6_996_000.0);
+    }
+
+    #[test]
+    fn test_struct_rider_death_benefit_01() {
+        // Share of the rider SI in rider_death_ben: ADD01 pays its SI, HOP02 has no SI
+        for id in RiderEnum::iter() {
+            let result = sample_rider(id).death_benefit().unwrap();
+            let expected = match id {
+                RiderEnum::ADD01 => 100_000_000.0,
+                _ => 0.0,
+            };
+            assert_eq!(result, expected, "{}", id.as_ref());
+        }
+    }
+}
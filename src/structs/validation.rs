@@ -0,0 +1,417 @@
+use super::*;
+use crate::database::age_validation::{
+    get_max_entry_age, get_min_entry_age, get_min_entry_month_age,
+};
+use crate::structs::helpers::json_pointer;
+use serde_json::{Value, json};
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn _number(value: &str) -> Value {
+    value
+        .parse::<f64>()
+        .map(|v| json!(v))
+        .unwrap_or_else(|_| json!(value))
+}
+
+// Code, parameters and text of a garde message. The custom field rules carry their code in the message;
+// the built-in range and length rules are read from garde's wording, with their bound.
+fn _garde_code(message: &str) -> (ValidationCodeEnum, Value, &str) {
+    use ValidationCodeEnum as Code;
+
+    if let Some((code, text)) = message
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+        && let Ok(code) = code.parse::<Code>()
+    {
+        return (code, json!({}), text);
+    }
+
+    let (code, params) = _builtin_code(message);
+    (code, params, message)
+}
+
+fn _builtin_code(message: &str) -> (ValidationCodeEnum, Value) {
+    use ValidationCodeEnum as Code;
+
+    if let Some(min) = message.strip_prefix("length is lower than ") {
+        return (Code::LengthBelowMinimum, json!({ "min": _number(min) }));
+    }
+    if let Some(max) = message.strip_prefix("length is greater than ") {
+        return (Code::LengthAboveMaximum, json!({ "max": _number(max) }));
+    }
+    if let Some(min) = message.strip_prefix("lower than ") {
+        return (Code::BelowMinimum, json!({ "min": _number(min) }));
+    }
+    if let Some(max) = message.strip_prefix("greater than ") {
+        return (Code::AboveMaximum, json!({ "max": _number(max) }));
+    }
+
+    (Code::InvalidValue, json!({}))
+}
+
+// Entry age against minimum, minimum in months and maximum entry ages, -1 when not set
+fn _age_limit_issues(
+    name: &str,
+    (min_age, min_month_age, max_age): (i32, i32, i32),
+    entry_age: i32,
+    entry_month_age: Option<i32>,
+    maturity_age: i32,
+    pointer: &str,
+) -> Vec<ValidationIssue> {
+    use ValidationCodeEnum as Code;
+
+    let mut issues = Vec::new();
+
+    if min_age != -1 && entry_age < min_age {
+        let err_msg = format!(
+            "Entry age {} is below the minimum entry age {} of {}.",
+            entry_age, min_age, name
+        );
+        let params = json!({ "entry_age": entry_age, "min": min_age, "product": name });
+        issues.push(ValidationIssue::error(
+            Code::EntryAgeBelowMinimum,
+            pointer,
+            err_msg,
+            params,
+        ));
+    }
+
+    // Juveniles below one year are checked on their age in months
+    if min_age == -1 && min_month_age == -1 {
+        let msg = format!("Minimum entry age of {} is left to manual review.", name);
+        let params = json!({ "limit": "min_entry_age", "product": name });
+        issues.push(ValidationIssue::warning(
+            Code::ManualReview,
+            pointer,
+            msg,
+            params,
+        ));
+    } else if min_month_age != -1
+        && let Some(month_age) = entry_month_age
+        && month_age < 12
+        && month_age < min_month_age
+    {
+        let err_msg = format!(
+            "Entry age {} months is below the minimum entry age {} months of {}.",
+            month_age, min_month_age, name
+        );
+        let params = json!({ "entry_month_age": month_age, "min": min_month_age, "product": name });
+        issues.push(ValidationIssue::error(
+            Code::EntryMonthAgeBelowMinimum,
+            pointer,
+            err_msg,
+            params,
+        ));
+    }
+
+    if max_age == -1 {
+        let msg = format!("Maximum entry age of {} is left to manual review.", name);
+        let params = json!({ "limit": "max_entry_age", "product": name });
+        issues.push(ValidationIssue::warning(
+            Code::ManualReview,
+            pointer,
+            msg,
+            params,
+        ));
+    } else if entry_age > max_age {
+        let err_msg = format!(
+            "Entry age {} is above the maximum entry age {} of {}.",
+            entry_age, max_age, name
+        );
+        let params = json!({ "entry_age": entry_age, "max": max_age, "product": name });
+        issues.push(ValidationIssue::error(
+            Code::EntryAgeAboveMaximum,
+            pointer,
+            err_msg,
+            params,
+        ));
+    }
+
+    if maturity_age == -1 {
+        let msg = format!("Maturity age of {} is left to manual review.", name);
+        let params = json!({ "limit": "maturity_age", "product": name });
+        issues.push(ValidationIssue::warning(
+            Code::ManualReview,
+            pointer,
+            msg,
+            params,
+        ));
+    } else if entry_age >= maturity_age {
+        let err_msg = format!(
+            "Entry age {} leaves no cover before the maturity age {} of {}.",
+            entry_age, maturity_age, name
+        );
+        let params =
+            json!({ "entry_age": entry_age, "maturity_age": maturity_age, "product": name });
+        issues.push(ValidationIssue::error(
+            Code::NoCoverBeforeMaturity,
+            pointer,
+            err_msg,
+            params,
+        ));
+    }
+
+    issues
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub code: ValidationCodeEnum,
+    pub pointer: String, // e.g. /base/withdrawal_plan/1/from
+    pub severity: SeverityEnum,
+    pub message: String, // English text of the code
+    pub params: Value,   // Values quoted in the message, keyed by name
+}
+
+impl ValidationIssue {
+    pub fn error(code: ValidationCodeEnum, pointer: &str, message: String, params: Value) -> Self {
+        Self {
+            code,
+            pointer: pointer.to_string(),
+            severity: SeverityEnum::Error,
+            message,
+            params,
+        }
+    }
+
+    pub fn warning(
+        code: ValidationCodeEnum,
+        pointer: &str,
+        message: String,
+        params: Value,
+    ) -> Self {
+        Self {
+            code,
+            pointer: pointer.to_string(),
+            severity: SeverityEnum::Warning,
+            message,
+            params,
+        }
+    }
+
+    // A rate table or calculation needed by a rule failed
+    pub fn lookup_failed(pointer: &str, what: &str, e: PolarsError) -> Self {
+        let err_msg = format!("Failed to get {}: {}", what, e);
+        let params = json!({ "value": what, "detail": e.to_string() });
+        Self::error(ValidationCodeEnum::LookupFailed, pointer, err_msg, params)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == SeverityEnum::Error
+    }
+}
+
+/// Error of a custom garde rule. The code leads the message, e.g. `[FundAllocEmpty] ...`, so that
+/// `garde_issues` reports it without reading the text.
+pub fn coded_error(code: ValidationCodeEnum, message: impl std::fmt::Display) -> garde::Error {
+    garde::Error::new(format!("[{}] {}", code.as_ref(), message))
+}
+
+/// Converts a garde report into coded issues.
+pub fn garde_issues(report: &garde::Report) -> Vec<ValidationIssue> {
+    report
+        .iter()
+        .map(|(path, err)| {
+            let (code, params, text) = _garde_code(err.message());
+            ValidationIssue::error(code, &json_pointer(path), text.to_string(), params)
+        })
+        .collect()
+}
+
+/// Entry age against the limits of a product or rider in age_validation.
+/// `entry_month_age` is only used below 12 completed months, whatever the age basis. A limit of -1 is not checked, and a warning
+/// refers the case to manual review unless another rule covers it.
+pub fn entry_age_issues<T>(
+    product: &T,
+    entry_age: i32,
+    entry_month_age: Option<i32>,
+    maturity_age: i32,
+    pointer: &str,
+) -> Vec<ValidationIssue>
+where
+    T: AsRef<str> + std::fmt::Debug,
+{
+    let limits = get_min_entry_age(product).and_then(|min_age| {
+        Ok((
+            min_age,
+            get_min_entry_month_age(product)?,
+            get_max_entry_age(product)?,
+        ))
+    });
+    match limits {
+        Ok(limits) => _age_limit_issues(
+            product.as_ref(),
+            limits,
+            entry_age,
+            entry_month_age,
+            maturity_age,
+            pointer,
+        ),
+        Err(e) => vec![ValidationIssue::lookup_failed(
+            pointer,
+            "entry age limits",
+            e,
+        )],
+    }
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ValidationCodeEnum as Code;
+
+    #[test]
+    fn test_fn_garde_code_01() {
+        // Built-in rules carry their bound
+        let (code, params, _) = _garde_code("greater than 2.5");
+        assert_eq!(code, ValidationCodeEnum::AboveMaximum);
+        assert_eq!(params, json!({ "max": 2.5 }));
+
+        let (code, params, _) = _garde_code("length is lower than 12");
+        assert_eq!(code, ValidationCodeEnum::LengthBelowMinimum);
+        assert_eq!(params, json!({ "min": 12.0 }));
+    }
+
+    #[test]
+    fn test_fn_garde_code_02() {
+        // Custom rules lead with their code, which is dropped from the text
+        let message = coded_error(ValidationCodeEnum::FundAllocEmpty, "No funds")
+            .message()
+            .to_string();
+        let (code, _, text) = _garde_code(&message);
+        assert_eq!(code, ValidationCodeEnum::FundAllocEmpty);
+        assert_eq!(text, "No funds");
+
+        // Messages without a known code fall back to InvalidValue, text untouched
+        for message in ["[NotACode] No funds", "No funds"] {
+            let (code, _, text) = _garde_code(message);
+            assert_eq!(code, ValidationCodeEnum::InvalidValue);
+            assert_eq!(text, message);
+        }
+    }
+
+    #[test]
+    fn test_fn_garde_issues_01() {
+        // Every field rule code comes out of a policy breaking it
+        use crate::helpers::read_json_struct;
+        use crate::structs::policy::Policy;
+
+        type Case = (ValidationCodeEnum, &'static str, fn(&mut Value));
+        let cases: [Case; 12] = [
+            (Code::BelowMinimum, "/base/si", |j| {
+                j["base"]["si"] = json!(-1.0)
+            }),
+            (Code::AboveMaximum, "/base/load/em_load", |j| {
+                j["base"]["load"]["em_load"] = json!(3.0)
+            }),
+            (Code::LengthBelowMinimum, "/owner/ssn", |j| {
+                j["owner"]["ssn"] = json!("123")
+            }),
+            (Code::LengthAboveMaximum, "/owner/ssn", |j| {
+                j["owner"]["ssn"] = json!("0123456789999")
+            }),
+            (Code::NotMultipleOfStep, "/base/load/em_load", |j| {
+                j["base"]["load"]["em_load"] = json!(0.3)
+            }),
+            (Code::FundAllocEmpty, "/base/fund_alloc", |j| {
+                j["base"]["fund_alloc"] = json!([])
+            }),
+            (Code::FundAllocTpSum, "/base/fund_alloc", |j| {
+                j["base"]["fund_alloc"][0]["tp_pct"] = json!(50)
+            }),
+            (Code::FundAllocEpSum, "/base/fund_alloc", |j| {
+                j["base"]["fund_alloc"][0]["ep_pct"] = json!(50)
+            }),
+            (
+                Code::WithdrawalFromAfterTo,
+                "/base/withdrawal_plan/0/from",
+                |j| j["base"]["withdrawal_plan"][0]["from"] = json!(8),
+            ),
+            (
+                Code::WithdrawalOverlap,
+                "/base/withdrawal_plan/1/from",
+                |j| j["base"]["withdrawal_plan"][1]["from"] = json!(6),
+            ),
+            (Code::IntRateCurveEmpty, "/base/int_rate_curve/high", |j| {
+                j["base"]["int_rate_curve"] = json!({ "basis": "PolicyYear", "high": [] })
+            }),
+            (Code::IntRateCurveOrder, "/base/int_rate_curve/high", |j| {
+                let high = json!([{ "from": 5, "rate": 0.05 }, { "from": 3, "rate": 0.04 }]);
+                j["base"]["int_rate_curve"] = json!({ "basis": "PolicyYear", "high": high })
+            }),
+        ];
+
+        let path = "src/structs/test_data/uvl01_policy.json";
+        for (code, pointer, break_rule) in cases {
+            let mut json: Value = read_json_struct(path).unwrap();
+            break_rule(&mut json);
+            let policy: Policy = serde_json::from_value(json).unwrap();
+            let issues = policy.validation_issues();
+            let found = issues.iter().find(|i| i.code == code);
+            assert_eq!(
+                found.map(|i| i.pointer.as_str()),
+                Some(pointer),
+                "{:?}",
+                code
+            );
+        }
+
+        let covered: Vec<_> = cases.iter().map(|(code, _, _)| *code).collect();
+        let field_rules = vec![
+            Code::BelowMinimum,
+            Code::AboveMaximum,
+            Code::LengthBelowMinimum,
+            Code::LengthAboveMaximum,
+            Code::NotMultipleOfStep,
+            Code::FundAllocEmpty,
+            Code::FundAllocTpSum,
+            Code::FundAllocEpSum,
+            Code::WithdrawalFromAfterTo,
+            Code::WithdrawalOverlap,
+            Code::IntRateCurveEmpty,
+            Code::IntRateCurveOrder,
+        ];
+        assert_eq!(covered, field_rules);
+    }
+
+    #[test]
+    fn test_fn_entry_age_issues_01() {
+        // UVL01: minimum entry age left to manual review, maximum 60
+        let issues = entry_age_issues(&ULEnum::UVL01, 61, None, 100, "/base/insured/dob");
+        let codes: Vec<_> = issues.iter().map(|i| i.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                ValidationCodeEnum::ManualReview,
+                ValidationCodeEnum::EntryAgeAboveMaximum
+            ]
+        );
+        assert_eq!(issues[1].params["max"], 60);
+
+        // A minimum in months applies to juveniles only
+        let limits = (-1, 1, 60);
+        let issues = _age_limit_issues("UVL01", limits, 0, Some(0), 100, "/base/insured/dob");
+        let codes: Vec<_> = issues.iter().map(|i| i.code).collect();
+        assert_eq!(codes, vec![ValidationCodeEnum::EntryMonthAgeBelowMinimum]);
+        assert_eq!(issues[0].params["entry_month_age"], 0);
+        let issues = _age_limit_issues("UVL01", limits, 1, Some(13), 100, "/base/insured/dob");
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_fn_entry_age_issues_02() {
+        // PPD01 has no minimum entry age at all
+        let issues = entry_age_issues(&RiderEnum::PPD01, 0, None, 71, "/rider/0/insured/dob");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, ValidationCodeEnum::ManualReview);
+        assert!(!issues[0].is_error());
+    }
+}
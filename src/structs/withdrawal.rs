@@ -1,12 +1,34 @@
 use super::*;
+use crate::structs::validation::coded_error;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+fn from_not_after_to_validation(to: i32) -> impl FnOnce(&i32, &()) -> garde::Result {
+    move |from, _ctx| {
+        // 'from' cannot be greater than 'to'
+        if *from > to {
+            let err_msg = format!(
+                "Withdrawal 'from' year ({}) cannot be greater than 'to' year ({}).",
+                from, to
+            );
+            return Err(coded_error(
+                ValidationCodeEnum::WithdrawalFromAfterTo,
+                err_msg,
+            ));
+        }
+        Ok(())
+    }
+}
 
 // -----------------------------------------------------------------------------
 // PUBLIC
 // -----------------------------------------------------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
 #[garde(allow_unvalidated)]
 pub struct Withdrawal {
     #[garde(range(min = 0))]
+    #[garde(custom(from_not_after_to_validation(self.to)))]
     pub from: i32, // policy year
 
     #[garde(range(min = 0))]
@@ -16,8 +38,80 @@ pub struct Withdrawal {
     pub amount: f64,
 }
 
+// The rows are validated together because each 'from' depends on the previous row's 'to'.
+// Validate is written by hand so the ordering error lands on the offending row, e.g. /1/from.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+#[schemars(transparent)]
+pub struct WithdrawalPlan(pub Vec<Withdrawal>);
+
+impl std::ops::Deref for WithdrawalPlan {
+    type Target = Vec<Withdrawal>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Validate for WithdrawalPlan {
+    type Context = ();
+
+    fn validate_into(
+        &self,
+        ctx: &Self::Context,
+        mut parent: &mut dyn FnMut() -> garde::Path,
+        report: &mut garde::Report,
+    ) {
+        for (i, row) in self.0.iter().enumerate() {
+            let mut path = garde::util::nested_path!(parent, i);
+            row.validate_into(ctx, &mut path, report);
+
+            // "from" value must be greater than previous "to" value
+            if let Some(prev_row) = i.checked_sub(1).map(|j| &self.0[j])
+                && row.from <= prev_row.to
+            {
+                let err_msg = format!(
+                    "Withdrawal 'from' year ({}) must be greater than previous 'to' year ({}).",
+                    row.from, prev_row.to
+                );
+                report.append(
+                    path().join("from"),
+                    coded_error(ValidationCodeEnum::WithdrawalOverlap, err_msg),
+                );
+            }
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 // UNIT TESTS
 // -----------------------------------------------------------------------------
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    fn plan(rows: &[(i32, i32)]) -> WithdrawalPlan {
+        WithdrawalPlan(
+            rows.iter()
+                .map(|&(from, to)| Withdrawal {
+                    from,
+                    to,
+                    amount: 1000.0,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_struct_withdrawal_plan_01() {
+        assert!(plan(&[(5, 6), (7, 10)]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_struct_withdrawal_plan_02() {
+        // Second row overlaps the first and the third row is reversed
+        let report = plan(&[(5, 6), (6, 8), (12, 10)]).validate().unwrap_err();
+        let paths: Vec<String> = report.iter().map(|(path, _)| path.to_string()).collect();
+        assert_eq!(paths, vec!["[1].from", "[2].from"]);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::cashflows::base_cf::base_cf;
+use crate::cashflows::scenario::{RunConfig, ScenarioSpec};
+use crate::helpers::df_to_json_records;
+use crate::structs::policy::Policy;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+// -----------------------------------------------------------------------------
+// PRIVATE
+// -----------------------------------------------------------------------------
+// The logic is kept free of wasm-bindgen types so it can be unit tested natively.
+fn _parse_and_validate(policy_json: &str) -> Result<Policy, String> {
+    let policy: Policy = serde_json::from_str(policy_json).map_err(|e| e.to_string())?;
+    policy
+        .validate_fields()
+        .map_err(|errors| serde_json::to_string(&errors).unwrap_or_default())?;
+    Ok(policy)
+}
+
+fn _illustrate(policy_json: &str, config_json: Option<&str>) -> Result<String, String> {
+    let policy = _parse_and_validate(policy_json)?;
+    let config: RunConfig = match config_json {
+        Some(json) => serde_json::from_str(json).map_err(|e| e.to_string())?,
+        None => RunConfig::default(),
+    };
+    let df = base_cf(&policy.base, &ScenarioSpec::All, &config)
+        .and_then(|lf| lf.collect())
+        .map_err(|e| e.to_string())?;
+    let records = df_to_json_records(&df).map_err(|e| e.to_string())?;
+    Ok(records.to_string())
+}
+
+// -----------------------------------------------------------------------------
+// PUBLIC
+// -----------------------------------------------------------------------------
+/// Validates a policy JSON string.
+/// Throws when invalid; validation failures carry a JSON array of `{code, pointer, severity, message, params}`.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = validatePolicy)]
+pub fn validate_policy(policy_json: &str) -> Result<(), JsError> {
+    _parse_and_validate(policy_json)
+        .map(|_| ())
+        .map_err(|e| JsError::new(&e))
+}
+
+/// Validates a policy JSON string and returns the base illustration as a JSON array of rows.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn illustrate(policy_json: &str) -> Result<String, JsError> {
+    _illustrate(policy_json, None).map_err(|e| JsError::new(&e))
+}
+
+/// Same as `illustrate`, with the run basis read from a config JSON string,
+/// e.g. `{"arithmetic": "Decimal"}`. Omitted keys keep their defaults.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = illustrateWithConfig)]
+pub fn illustrate_with_config(policy_json: &str, config_json: &str) -> Result<String, JsError> {
+    _illustrate(policy_json, Some(config_json)).map_err(|e| JsError::new(&e))
+}
+
+/// Returns the JSON Schema of the policy input.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = policySchema)]
+pub fn policy_schema() -> String {
+    Policy::json_schema().to_string()
+}
+
+// -----------------------------------------------------------------------------
+// UNIT TESTS
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_illustrate_01() {
+        let json = std::fs::read_to_string("src/cashflows/test_data/uvl01_policy.json").unwrap();
+        let result: serde_json::Value =
+            serde_json::from_str(&_illustrate(&json, None).unwrap()).unwrap();
+        let rows = result.as_array().unwrap();
+        assert!(!rows.is_empty());
+        assert_eq!(rows[0]["year"], 1);
+        assert_eq!(rows[0]["int_rate_scenario"], "High");
+    }
+
+    #[test]
+    fn test_fn_illustrate_02() {
+        let json = std::fs::read_to_string("src/cashflows/test_data/uvl01_policy.json").unwrap();
+        let config = r#"{"arithmetic": "Decimal", "shock": {"coi_rate_mult": 1.1}}"#;
+        let result: serde_json::Value =
+            serde_json::from_str(&_illustrate(&json, Some(config)).unwrap()).unwrap();
+        assert!(!result.as_array().unwrap().is_empty());
+
+        assert!(_illustrate(&json, Some("{\"arithmetic\": 1}")).is_err());
+    }
+
+    #[test]
+    fn test_fn_parse_and_validate_01() {
+        let result = _parse_and_validate("{\"id\": ");
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * Exercises the C ABI in include/si.h.
+ *
+ * Build and run from the repository root (rate tables are read relative to it):
+ *   cargo build --release
+ *   cc -Iinclude tests/c/ffi_test.c -Ltarget/release -lsi -o target/ffi_test
+ *   LD_LIBRARY_PATH=target/release ./target/ffi_test
+ */
+#include "si.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(cond, msg)                                   \
+    do {                                                   \
+        if (!(cond)) {                                     \
+            fprintf(stderr, "FAIL: %s (line %d)\n", msg, __LINE__); \
+            failures++;                                    \
+        }                                                  \
+    } while (0)
+
+static char *read_file(const char *path) {
+    FILE *f = fopen(path, "rb");
+    if (!f) return NULL;
+    fseek(f, 0, SEEK_END);
+    long size = ftell(f);
+    fseek(f, 0, SEEK_SET);
+    char *buf = malloc((size_t)size + 1);
+    if (buf && fread(buf, 1, (size_t)size, f) != (size_t)size) {
+        free(buf);
+        buf = NULL;
+    }
+    if (buf) buf[size] = '\0';
+    fclose(f);
+    return buf;
+}
+
+int main(void) {
+    CHECK(si_abi_version() == SI_ABI_VERSION, "ABI version mismatch");
+    CHECK(strlen(si_engine_version()) > 0, "empty engine version");
+
+    char *policy = read_file("src/cashflows/test_data/uvl01_policy.json");
+    CHECK(policy != NULL, "cannot read policy JSON");
+
+    SiBuffer out;
+    SiStatus status;
+
+    /* Valid policy, CSV output */
+    status = si_illustrate(policy, SI_OUTPUT_FORMAT_CSV, &out);
+    CHECK(status == SI_STATUS_OK, "CSV illustration failed");
+    CHECK(out.len > 0 && strncmp((const char *)out.data, "year,", 5) == 0, "unexpected CSV header");
+    si_buffer_free(out);
+
+    /* Valid policy, JSON output */
+    status = si_illustrate(policy, SI_OUTPUT_FORMAT_JSON, &out);
+    CHECK(status == SI_STATUS_OK, "JSON illustration failed");
+    CHECK(out.len > 0 && out.data[0] == '[', "unexpected JSON payload");
+    si_buffer_free(out);
+
+    /* Run config chosen by the caller */
+    status = si_illustrate_with_config(policy, "{\"arithmetic\": \"Decimal\"}", SI_OUTPUT_FORMAT_CSV, &out);
+    CHECK(status == SI_STATUS_OK, "decimal illustration failed");
+    si_buffer_free(out);
+
+    status = si_illustrate_with_config(policy, "{\"arithmetic\": ", SI_OUTPUT_FORMAT_CSV, &out);
+    CHECK(status == SI_STATUS_PARSE_ERROR, "expected config parse error");
+    si_buffer_free(out);
+
+    /* Unknown format flag */
+    status = si_illustrate(policy, 42, &out);
+    CHECK(status == SI_STATUS_INVALID_FORMAT, "expected invalid format");
+    si_buffer_free(out);
+
+    /* Malformed JSON carries an error message */
+    status = si_illustrate("{\"id\": ", SI_OUTPUT_FORMAT_CSV, &out);
+    CHECK(status == SI_STATUS_PARSE_ERROR, "expected parse error");
+    CHECK(out.len > 0, "missing error message");
+    si_buffer_free(out);
+
+    /* Null arguments */
+    status = si_illustrate(NULL, SI_OUTPUT_FORMAT_CSV, &out);
+    CHECK(status == SI_STATUS_NULL_ARGUMENT, "expected null argument");
+    si_buffer_free(out);
+    CHECK(si_illustrate(policy, SI_OUTPUT_FORMAT_CSV, NULL) == SI_STATUS_NULL_ARGUMENT,
+          "expected null out pointer");
+
+    free(policy);
+
+    if (failures == 0) {
+        printf("ffi_test: all checks passed\n");
+        return 0;
+    }
+    fprintf(stderr, "ffi_test: %d check(s) failed\n", failures);
+    return 1;
+}